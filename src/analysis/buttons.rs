@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 
 use log::debug;
 
@@ -9,18 +9,21 @@ use memflow::prelude::v1::*;
 use pelite::pattern;
 use pelite::pe64::{Pe, PeView};
 
+use super::{DumperError, read_module};
+
 use crate::source2::KeyButton;
 
 pub type ButtonMap = BTreeMap<String, imem>;
 
-pub fn buttons<P: Process + MemoryView>(process: &mut P) -> Result<ButtonMap> {
-    let module = process.module_by_name("client.dll")?;
+pub fn buttons<P: Process + MemoryView>(process: &mut P) -> Result<ButtonMap, DumperError> {
+    let module = process
+        .module_by_name("client.dll")
+        .map_err(|_| DumperError::ModuleNotFound("client.dll".to_string()))?;
 
-    let buf = process
-        .read_raw(module.base, module.size as _)
-        .data_part()?;
+    let buf = read_module(process, &module)?;
 
-    let view = PeView::from_bytes(&buf)?;
+    let view = PeView::from_bytes(&buf)
+        .map_err(|source| DumperError::invalid_image(module.name.as_ref(), &buf, source))?;
 
     let mut save = [0; 2];
 
@@ -28,7 +31,7 @@ pub fn buttons<P: Process + MemoryView>(process: &mut P) -> Result<ButtonMap> {
         .scanner()
         .finds_code(pattern!("488b15${'} 4885d2 74? 488b02 4885c0"), &mut save)
     {
-        bail!("outdated button list pattern");
+        return Err(DumperError::OutdatedPattern("button list"));
     }
 
     read_buttons(process, &module, module.base + save[1])
@@ -38,7 +41,7 @@ fn read_buttons(
     mem: &mut impl MemoryView,
     module: &ModuleInfo,
     list_addr: Address,
-) -> Result<ButtonMap> {
+) -> Result<ButtonMap, DumperError> {
     let mut map = ButtonMap::new();
 
     let mut cur_button = Pointer64::<KeyButton>::from(mem.read_addr64(list_addr).data_part()?);
@@ -0,0 +1,40 @@
+use iced_x86::{Decoder, DecoderOptions, Formatter, IntelFormatter};
+
+/// Disassembles up to `count` instructions from the start of `code`, which is located at `ip`.
+pub fn disassemble(code: &[u8], ip: u64, count: usize) -> Vec<String> {
+    let mut decoder = Decoder::with_ip(64, code, ip, DecoderOptions::NONE);
+    let mut formatter = IntelFormatter::new();
+
+    let mut lines = Vec::new();
+
+    while decoder.can_decode() && lines.len() < count {
+        let instruction = decoder.decode();
+
+        if instruction.is_invalid() {
+            break;
+        }
+
+        let mut line = String::new();
+
+        formatter.format(&instruction, &mut line);
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mov_ret() {
+        // mov rax, [rip+0x10]; ret
+        let lines = disassemble(&[0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00, 0xC3], 0x1000, 3);
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("mov rax,"));
+        assert_eq!(lines[1], "ret");
+    }
+}
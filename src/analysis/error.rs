@@ -0,0 +1,75 @@
+use thiserror::Error;
+
+/// The ways in which the analysis can fail, such as resolving offsets or loading its configuration.
+#[derive(Debug, Error)]
+pub enum DumperError {
+    /// The module isn't loaded in the process or couldn't be found on disk.
+    #[error("module not found: {0}")]
+    ModuleNotFound(String),
+
+    /// There are no patterns for the module.
+    #[error("no patterns for module: {0}")]
+    UnknownModule(String),
+
+    /// The memory or file of a module couldn't be read.
+    #[error("failed to read {0}")]
+    ReadFailed(String),
+
+    /// The bytes of a module aren't a valid PE image, usually because it was only partially read.
+    #[error(
+        "invalid image of {module_name}: {source} ({len} bytes read, {} expected)",
+        .expected.map_or("unknown".to_string(), |size| size.to_string())
+    )]
+    InvalidImage {
+        module_name: String,
+        len: usize,
+        /// The image size from the PE headers, if they could be read.
+        expected: Option<usize>,
+        source: pelite::Error,
+    },
+
+    /// The bytes at a resolved offset don't match the signature of its pattern.
+    #[error("signature mismatch: {0}")]
+    SignatureMismatch(String),
+
+    /// Cross-module callbacks depend on each other, so there's no order to resolve the modules in.
+    #[error("circular module dependency: {}", .0.join(", "))]
+    DependencyCycle(Vec<String>),
+
+    /// Offsets that aren't marked as optional didn't match.
+    #[error("missing required offsets: {}", .0.join(", "))]
+    NoMatches(Vec<String>),
+
+    /// The patterns of these offsets matched, but their callbacks failed to derive other offsets.
+    #[error("callbacks failed after their patterns matched: {}", .0.join(", "))]
+    CallbackFailed(Vec<String>),
+
+    /// A module is registered that's already scanned.
+    #[error("module is already scanned: {0}")]
+    DuplicateModule(String),
+
+    /// A pattern couldn't be parsed, such as one passed to `--override-pattern`.
+    #[error("invalid pattern for {0}")]
+    InvalidPattern(String),
+
+    /// The pattern that locates a list in a module, such as the button list, didn't match.
+    #[error("outdated {0} pattern")]
+    OutdatedPattern(&'static str),
+
+    /// A file or argument that configures the dump, such as `--dll-names`, is malformed.
+    #[error("{0}")]
+    InvalidConfig(String),
+
+    /// A configuration that can only be loaded once, such as the DLL names, was loaded again.
+    #[error("{0} are already in use")]
+    AlreadyLoaded(&'static str),
+
+    /// The data read from the process doesn't have the expected contents, such as an empty schema
+    /// system.
+    #[error("invalid data: {0}")]
+    InvalidData(&'static str),
+
+    /// The process memory couldn't be read.
+    #[error("memory error: {0}")]
+    Memory(#[from] memflow::error::Error),
+}
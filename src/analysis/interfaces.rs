@@ -9,20 +9,19 @@ use memflow::prelude::v1::*;
 use pelite::pe64::exports::Export;
 use pelite::pe64::{Pe, PeView};
 
+use super::{DumperError, read_module};
+
 use crate::source2::InterfaceReg;
 
 pub type InterfaceMap = BTreeMap<String, BTreeMap<String, umem>>;
 
-pub fn interfaces<P: Process + MemoryView>(process: &mut P) -> Result<InterfaceMap> {
+pub fn interfaces<P: Process + MemoryView>(process: &mut P) -> Result<InterfaceMap, DumperError> {
     process
         .module_list()?
         .iter()
         .filter(|module| module.name.as_ref() != "crashandler64.dll")
         .filter_map(|module| {
-            let buf = process
-                .read_raw(module.base, module.size as _)
-                .data_part()
-                .ok()?;
+            let buf = read_module(process, module).ok()?;
 
             let view = PeView::from_bytes(&buf).ok()?;
 
@@ -52,7 +51,7 @@ fn read_interfaces(
     mem: &mut impl MemoryView,
     module: &ModuleInfo,
     list_addr: Address,
-) -> Result<BTreeMap<String, umem>> {
+) -> Result<BTreeMap<String, umem>, DumperError> {
     let mut ifaces = BTreeMap::new();
 
     let mut cur_reg = Pointer64::<InterfaceReg>::from(mem.read_addr64(list_addr).data_part()?);
@@ -79,7 +78,7 @@ fn read_interfaces(
     Ok(ifaces)
 }
 
-fn read_addr64_rip(mem: &mut impl MemoryView, addr: Address) -> Result<Address> {
+fn read_addr64_rip(mem: &mut impl MemoryView, addr: Address) -> Result<Address, DumperError> {
     let disp = mem.read::<i32>(addr + 0x3).data_part()?;
 
     Ok(addr + 0x7 + disp)
@@ -1,35 +1,68 @@
 pub use buttons::*;
+pub use error::*;
 pub use interfaces::*;
 pub use offsets::*;
 pub use schemas::*;
+pub use summary::*;
 
-use std::any::type_name;
+use std::collections::BTreeMap;
+use std::ops::Range;
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
-use log::{error, info};
+use log::{error, info, warn};
 
 use memflow::prelude::v1::*;
 
+use pelite::pe64::Rva;
+
 mod buttons;
+#[cfg(feature = "disasm")]
+mod disasm;
+mod error;
 mod interfaces;
 mod offsets;
 mod schemas;
+mod summary;
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct AnalysisResult {
     pub buttons: ButtonMap,
     pub interfaces: InterfaceMap,
     pub offsets: OffsetMap,
     pub schemas: SchemaMap,
+    /// Empty unless requested with `--disasm`.
+    pub disasm: DisasmMap,
+    /// Empty unless requested with `--include-abs`.
+    pub module_bases: ModuleBases,
+    /// The transforms of the offsets in the generated files, from `--transform`. The offsets
+    /// themselves are kept as they were resolved.
+    pub transforms: TransformMap,
+    /// The offsets that were resolved, but whose callbacks failed to derive other offsets.
+    pub failed_callbacks: FailedCallbacks,
+    /// The source of every pattern, including those of listed modules, for `hpp_bundle`.
+    pub patterns: PatternMap,
 }
 
-pub fn analyze_all<P: Process + MemoryView>(process: &mut P) -> Result<AnalysisResult> {
-    let buttons = analyze(process, buttons);
+/// Runs all analyses. Failures are logged and leave the affected results empty, unless `strict` is
+/// set, in which case the first failure is returned.
+///
+/// The offsets of each module are passed to `sink` as soon as they've been resolved.
+pub fn analyze_all<P, S>(
+    process: &mut P,
+    registry: &Registry,
+    strict: bool,
+    sink: S,
+) -> Result<AnalysisResult>
+where
+    P: Process + MemoryView,
+    S: FnMut(&str, &BTreeMap<String, Rva>),
+{
+    let buttons = analyze(process, "buttons", strict, buttons)?;
 
     info!("found {} buttons", buttons.len());
 
-    let interfaces = analyze(process, interfaces);
+    let interfaces = analyze(process, "interfaces", strict, interfaces)?;
 
     info!(
         "found {} interfaces across {} modules",
@@ -40,7 +73,12 @@ pub fn analyze_all<P: Process + MemoryView>(process: &mut P) -> Result<AnalysisR
         interfaces.len()
     );
 
-    let offsets = analyze(process, offsets);
+    let mut offsets = analyze(process, "offsets", strict, |process| {
+        offsets_incremental(process, registry, strict, sink)
+    })?;
+
+    add_cvar_offset(&mut offsets, &interfaces);
+    add_constants(&mut offsets);
 
     info!(
         "found {} offsets across {} modules",
@@ -51,7 +89,7 @@ pub fn analyze_all<P: Process + MemoryView>(process: &mut P) -> Result<AnalysisR
         offsets.len()
     );
 
-    let schemas = analyze(process, schemas);
+    let schemas = analyze(process, "schemas", strict, schemas)?;
 
     let (class_count, enum_count) =
         schemas
@@ -72,23 +110,164 @@ pub fn analyze_all<P: Process + MemoryView>(process: &mut P) -> Result<AnalysisR
         interfaces,
         offsets,
         schemas,
+        disasm: DisasmMap::new(),
+        module_bases: ModuleBases::new(),
+        transforms: TransformMap::new(),
+        failed_callbacks: failed_callbacks(),
+        patterns: registry.patterns(),
     })
 }
 
-fn analyze<P, F, T>(process: &mut P, f: F) -> T
+fn analyze<P, F, T>(process: &mut P, name: &str, strict: bool, f: F) -> Result<T>
 where
     P: Process + MemoryView,
-    F: FnOnce(&mut P) -> Result<T>,
+    F: FnOnce(&mut P) -> Result<T, DumperError>,
     T: Default,
 {
-    let name = type_name::<F>();
-
     match f(process) {
-        Ok(result) => result,
+        Ok(result) => Ok(result),
+        Err(err) if strict => Err(anyhow!(err).context(format!("failed to read {}", name))),
         Err(err) => {
             error!("failed to read {}: {}", name, err);
 
-            T::default()
+            Ok(T::default())
         }
     }
 }
+
+/// Reads the image of `module` in a single read, falling back to one page at a time if that fails,
+/// so that unreadable pages are zero-filled and logged instead of failing the read as a whole.
+pub fn read_module<P: MemoryView>(
+    process: &mut P,
+    module: &ModuleInfo,
+) -> Result<Vec<u8>, DumperError> {
+    let (buf, holes) = read_pages(module.size as usize, |offset, chunk| {
+        process
+            .read_raw_into(module.base + offset as umem, chunk)
+            .is_ok()
+    });
+
+    if holes.len() == 1 && holes[0] == (0..buf.len()) {
+        return Err(DumperError::ReadFailed(module.name.to_string()));
+    }
+
+    for hole in &holes {
+        warn!(
+            "unreadable memory in {}: {:#X}..{:#X}",
+            module.name, hole.start, hole.end
+        );
+    }
+
+    Ok(buf)
+}
+
+/// Reads a NUL-terminated UTF-16 string of at most `max_len` code units at `addr`.
+pub fn read_utf16<P: MemoryView>(process: &mut P, addr: Address, max_len: usize) -> Result<String> {
+    let mut buf = vec![0; max_len * 2];
+
+    process.read_raw_into(addr, &mut buf).data_part()?;
+
+    decode_utf16(&buf)
+}
+
+fn decode_utf16(buf: &[u8]) -> Result<String> {
+    let units: Vec<u16> = buf
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+
+    Ok(String::from_utf16(&units)?)
+}
+
+fn read_pages<F>(size: usize, mut read: F) -> (Vec<u8>, Vec<Range<usize>>)
+where
+    F: FnMut(usize, &mut [u8]) -> bool,
+{
+    const PAGE_SIZE: usize = 0x1000;
+
+    let mut buf = vec![0; size];
+    let mut holes: Vec<Range<usize>> = Vec::new();
+
+    if read(0, &mut buf) {
+        return (buf, holes);
+    }
+
+    for (i, chunk) in buf.chunks_mut(PAGE_SIZE).enumerate() {
+        let offset = i * PAGE_SIZE;
+
+        if read(offset, chunk) {
+            continue;
+        }
+
+        chunk.fill(0);
+
+        // Merge adjacent unreadable pages into a single range.
+        match holes.last_mut() {
+            Some(hole) if hole.end == offset => hole.end += chunk.len(),
+            _ => holes.push(offset..offset + chunk.len()),
+        }
+    }
+
+    (buf, holes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16_string() -> Result<()> {
+        let mut buf: Vec<u8> = "de_dust2 ✓"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        buf.extend([0, 0, b'x', 0]);
+
+        assert_eq!(decode_utf16(&buf)?, "de_dust2 ✓");
+        assert_eq!(decode_utf16(&[b'a', 0, b'b', 0])?, "ab");
+        assert!(decode_utf16(&[0x00, 0xD8, 0, 0]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_pages_with_hole() {
+        let (buf, holes) = read_pages(0x4800, |offset, chunk| {
+            if offset < 0x3000 && offset + chunk.len() > 0x1000 {
+                chunk.fill(0xFF);
+
+                return false;
+            }
+
+            chunk.fill(0xCC);
+
+            true
+        });
+
+        assert_eq!(buf.len(), 0x4800);
+        assert_eq!(holes, [0x1000..0x3000]);
+
+        assert!(buf[..0x1000].iter().all(|&b| b == 0xCC));
+        assert!(buf[0x1000..0x3000].iter().all(|&b| b == 0));
+        assert!(buf[0x3000..].iter().all(|&b| b == 0xCC));
+    }
+
+    #[test]
+    fn read_pages_at_once() {
+        let mut reads = Vec::new();
+
+        let (buf, holes) = read_pages(0x4800, |offset, chunk| {
+            reads.push((offset, chunk.len()));
+
+            chunk.fill(0xCC);
+
+            true
+        });
+
+        assert_eq!(reads, [(0, 0x4800)]);
+        assert!(holes.is_empty());
+        assert!(buf.iter().all(|&b| b == 0xCC));
+    }
+}
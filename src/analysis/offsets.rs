@@ -1,69 +1,326 @@
-use std::collections::BTreeMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 
-use log::{debug, error};
+use log::{Level, debug, error, log_enabled, warn};
 
 use memflow::prelude::v1::*;
 
+use pelite::FileMap;
 use pelite::pattern;
 use pelite::pattern::{Atom, save_len};
-use pelite::pe64::{Pe, PeView, Rva};
+use pelite::pe64::exports::Export;
+use pelite::pe64::{Pe, PeFile, PeView, Rva};
 
 use phf::{Map, phf_map};
 
+use super::{DumperError, InterfaceMap, read_module};
+
 pub type OffsetMap = BTreeMap<String, BTreeMap<String, Rva>>;
 
+/// The first instructions at the match location of each pattern, keyed by module and offset name.
+pub type DisasmMap = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+/// Overrides of the DLL that each `pattern_map!` module is resolved in, keyed by module name.
+pub type DllNames = BTreeMap<String, String>;
+
+/// The runtime base address of each module, by module name.
+pub type ModuleBases = BTreeMap<String, u64>;
+
+/// The source of every pattern as written in `pattern_map!`, by module and offset name.
+pub type PatternMap = BTreeMap<String, BTreeMap<String, String>>;
+
+/// The offsets whose pattern matched but whose callback failed to derive anything, by module.
+pub type FailedCallbacks = BTreeMap<String, BTreeSet<String>>;
+
+/// How each offset is calculated, such as `dwPrediction + 0x180`, by module and offset name.
+pub type ExpressionMap = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A pattern to scan for, either compiled into the binary with `pattern!` or parsed at runtime, so
+/// that both are scanned the same way.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CompiledPattern {
+    /// A pattern parsed at compile time, such as those in `pattern_map!`.
+    Static(&'static [Atom]),
+    /// A pattern parsed at runtime, such as those passed to `--override-pattern`.
+    Parsed(Vec<Atom>),
+}
+
+impl CompiledPattern {
+    /// Parses a pattern written in the syntax of `pattern!`.
+    pub fn parse(source: &str) -> Result<Self, pattern::ParsePatError> {
+        pattern::parse(source).map(Self::Parsed)
+    }
+
+    pub fn atoms(&self) -> &[Atom] {
+        match self {
+            Self::Static(atoms) => atoms,
+            Self::Parsed(atoms) => atoms,
+        }
+    }
+}
+
+/// A pattern replaced at runtime with `--override-pattern`.
+#[derive(Clone, Debug)]
+pub struct PatternOverride {
+    /// The `pattern_map!` module, such as `client`.
+    pub module: String,
+    /// The name of the offset.
+    pub name: String,
+    /// The parsed replacement pattern.
+    pub pattern: CompiledPattern,
+    /// The replacement pattern as written on the command line.
+    pub source: &'static str,
+}
+
+/// A DLL listed with `--modules-from-file`, which is scanned with the patterns listed for it.
+#[derive(Clone, Debug)]
+pub struct ListedModule {
+    /// The name of the DLL, such as `vstdlib.dll`.
+    pub dll_name: String,
+    /// The patterns of the module as `(name, pattern, source)`, where `source` is the pattern as
+    /// written in the file.
+    pub patterns: Vec<(String, CompiledPattern, String)>,
+}
+
+type Callback = fn(&PeView, &mut BTreeMap<String, Rva>, Rva);
+
+type Patterns = Map<&'static str, (&'static [Atom], Option<Callback>, PatternAttrs)>;
+
+/// Optional per-pattern attributes, set with `#[name]` or `#[name = value]` before an entry in
+/// `pattern_map!`.
+#[derive(Clone, Copy, Debug)]
+pub struct PatternAttrs {
+    /// Bytes expected at the resolved RVA. Checked after the module has been scanned.
+    pub signature: &'static [u8],
+    /// Abort the dump instead of only logging a warning if the signature doesn't match.
+    pub fatal: bool,
+    /// The offset is still dumped, but marked as deprecated in the generated files.
+    pub deprecated: bool,
+    /// A missing offset is only logged, even with `--fail-on-missing`.
+    pub optional: bool,
+    /// Only accept matches that start at a multiple of this many bytes. `0` and `1` accept any
+    /// match.
+    pub align: u32,
+    /// The name of the section to scan, such as `.rdata`, instead of every executable section.
+    pub section: &'static str,
+    /// Only scan from the start RVA up to the end RVA, such as `(0x10000, 0x20000)`, within the
+    /// scanned section. `(0, 0)` scans the whole section.
+    pub scan_range: (Rva, Rva),
+    /// The game feature the offset belongs to, such as `rendering`, for `--feature`.
+    pub feature: &'static str,
+    /// The function or global that the offset refers to, such as `gpGlobals`, for reviewers.
+    pub target: &'static str,
+    /// The layout of the value at the offset, such as `float[4][4] row-major`, for offsets of
+    /// values other than pointers, which consumers would otherwise have to guess.
+    pub layout: &'static str,
+    /// The capture is a sign-extended displacement, read with `i1`, `i2` or `i4`, such as that of
+    /// a `movsxd`. Negative values are written as such instead of as huge unsigned offsets.
+    pub signed: bool,
+    /// The pattern as written in `pattern_map!`, or the export name for `export!` entries. Set
+    /// automatically.
+    pub source: &'static str,
+    /// Resolved from the export named by `source` instead of by scanning. Set automatically.
+    pub export: bool,
+}
+
+/// How far an offset can be trusted, based on how often its pattern matched in the scanned range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    /// The pattern matched more than once, so the first match may not be the intended one, or it
+    /// matched outside of executable code, such as in a section named by `#[section]`.
+    Low,
+    /// The pattern matched exactly once in executable code, or the offset was resolved from an
+    /// export.
+    High,
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+impl PatternAttrs {
+    pub const DEFAULT: Self = Self {
+        signature: &[],
+        fatal: false,
+        deprecated: false,
+        optional: false,
+        align: 0,
+        section: "",
+        scan_range: (0, 0),
+        feature: "",
+        target: "",
+        layout: "",
+        signed: false,
+        source: "",
+        export: false,
+    };
+}
+
+macro_rules! attr_value {
+    () => {
+        true
+    };
+    ($value:expr) => {
+        $value
+    };
+}
+
+/// The atoms of an entry in `pattern_map!`. Exports don't have any.
+macro_rules! entry_atoms {
+    (pattern, $pattern:literal) => {
+        pattern!($pattern)
+    };
+    (export, $name:literal) => {
+        &[]
+    };
+}
+
+macro_rules! is_export {
+    (pattern) => {
+        false
+    };
+    (export) => {
+        true
+    };
+}
+
+/// Entries are either `pattern!("...")` to scan for a pattern, or `export!("...")` to take the
+/// RVA of an exported symbol.
 macro_rules! pattern_map {
     ($($module:ident => {
-        $($name:expr => $pattern:expr $(=> $callback:expr)?),+ $(,)?
+        $($(#[$attr:ident $(= $value:expr)?])* $name:literal => $kind:ident!($pattern:literal) $(=> $callback:expr)?),+ $(,)?
     }),+ $(,)?) => {
         $(
             mod $module {
                 use super::*;
 
-                pub(super) const PATTERNS: Map<
-                    &'static str,
-                    (
-                        &'static [Atom],
-                        Option<fn(&PeView, &mut BTreeMap<String, Rva>, Rva)>,
-                    ),
-                > = phf_map! {
-                    $($name => ($pattern, $($callback)?)),+
+                pub(super) const PATTERNS: Patterns = phf_map! {
+                    $($name => (
+                        entry_atoms!($kind, $pattern),
+                        $($callback)?,
+                        PatternAttrs {
+                            source: $pattern,
+                            export: is_export!($kind),
+                            $($attr: attr_value!($($value)?),)*
+                            ..PatternAttrs::DEFAULT
+                        },
+                    )),+
                 };
 
-                pub fn offsets(view: PeView<'_>) -> BTreeMap<String, Rva> {
+                pub fn offsets(view: PeView<'_>) -> Result<BTreeMap<String, Rva>, DumperError> {
                     let mut map = BTreeMap::new();
+                    let mut callbacks = Vec::new();
 
-                    for (&name, (pat, callback)) in &PATTERNS {
-                        let mut save = vec![0; save_len(pat)];
-
-                        if !view.scanner().finds_code(pat, &mut save) {
-                            error!("outdated pattern: {}", name);
+                    for (&name, (pat, callback, attrs)) in &PATTERNS {
+                        let builtin = CompiledPattern::Static(*pat);
+                        let pat = pattern_override(stringify!($module), name).unwrap_or(&builtin);
 
+                        let Some((rva, confidence)) = resolve_entry(&view, name, pat, attrs) else {
                             continue;
-                        }
+                        };
 
-                        let rva = save[1];
+                        record_confidence(
+                            dll_name(stringify!($module)).unwrap_or(stringify!($module)),
+                            name,
+                            confidence,
+                        );
 
                         map.insert(name.to_string(), rva);
 
+                        if attrs.signed {
+                            record_signed(
+                                dll_name(stringify!($module)).unwrap_or(stringify!($module)),
+                                name,
+                            );
+                        }
+
                         if let Some(callback) = callback {
-                            callback(&view, &mut map, rva);
+                            callbacks.push((name, *callback, rva));
+                        }
+                    }
+
+                    // Callbacks run once every pattern has been scanned, so that an offset they
+                    // overwrite is detected whichever order the patterns are scanned in.
+                    for (name, callback, rva) in callbacks {
+                        let module_name = dll_name(stringify!($module)).unwrap_or(stringify!($module));
+                        let known = map.clone();
+
+                        callback(&view, &mut map, rva);
+
+                        for key in PENDING_SIGNED.with_borrow_mut(std::mem::take) {
+                            record_signed(module_name, &key);
+                        }
+
+                        for (key, old, new) in overwritten_offsets(name, &known, &map) {
+                            warn!(
+                                "callback of {} overwrote {} ({:#X} -> {:#X}), keeping the previous value",
+                                name, key, old, new
+                            );
+
+                            map.insert(key, old);
+                        }
+
+                        // Callbacks log why they failed, so leaving the offsets as they were is
+                        // how their failure shows, even though the pattern itself matched.
+                        if map == known {
+                            error!("callback of {} failed, keeping only its own offset", name);
+
+                            record_failed_callback(module_name, name);
+                        }
+
+                        record_derived(
+                            module_name,
+                            name,
+                            map.keys().filter(|key| !known.contains_key(*key)),
+                        );
+                    }
+
+                    for (&name, (_, _, attrs)) in &PATTERNS {
+                        if attrs.signature.is_empty() {
+                            continue;
+                        }
+
+                        let Some(&rva) = map.get(name) else {
+                            continue;
+                        };
+
+                        if !verify_signature(&view, name, rva, attrs.signature) && attrs.fatal {
+                            return Err(DumperError::SignatureMismatch(name.to_string()));
                         }
                     }
 
                     for (name, value) in &map {
+                        let target = PATTERNS
+                            .get(name.as_str())
+                            .map(|(_, _, attrs)| attrs.target)
+                            .filter(|target| !target.is_empty())
+                            .map(|target| format!(" [{}]", target))
+                            .unwrap_or_default();
+
                         debug!(
-                            "found offset: {} at {:#X} ({}.dll + {:#X})",
+                            "found offset: {} at {:#X} ({}.dll + {:#X}){}",
                             name,
                             *value as u64 + view.optional_header().ImageBase,
                             stringify!($module),
-                            value
+                            value,
+                            target
                         );
                     }
 
-                    map
+                    Ok(map)
                 }
             }
         )+
@@ -72,230 +329,3993 @@ macro_rules! pattern_map {
 
 pattern_map! {
     client => {
+        #[feature = "input"]
         "dwCSGOInput" => pattern!("488905${'} 0f57c0 0f1105") => Some(|view, map, rva| {
             let mut save = [0; 2];
 
-            if view.scanner().finds_code(pattern!("f2410f108430u4"), &mut save) {
-                map.insert("dwViewAngles".to_string(), rva + save[1]);
+            if !view.scanner().finds_code(pattern!("f2410f108430u4"), &mut save) {
+                error!("outdated pattern: dwViewAngles");
+
+                return;
+            }
+
+            // The view angles are a field of the input object, so anything beyond a small
+            // displacement means the pattern matched the wrong instruction.
+            if save[1] == 0 || save[1] > 0x10000 {
+                warn!("implausible offset: dwViewAngles (dwCSGOInput + {:#X})", save[1]);
+
+                return;
+            }
+
+            if let Some(value) = checked_offset("dwViewAngles", rva, save[1] as i64) {
+                map.insert("dwViewAngles".to_string(), value);
             }
         }),
+        #[signature = CREATE_INTERFACE]
+        "dwCreateInterface" => export!("CreateInterface") => Some(interface_regs),
         "dwEntityList" => pattern!("488935${'} 4885f6") => None,
         "dwGameEntitySystem" => pattern!("488b1d${'} 48891d") => None,
         "dwGameEntitySystem_highestEntityIndex" => pattern!("8b81u2?? 8902 488bc2 c3 cccccccc 48895c24? 48896c24") => None,
         "dwGameRules" => pattern!("48891d${'} ff15${} 84c0") => None,
+        #[target = "gpGlobals"]
         "dwGlobalVars" => pattern!("488915${'} 488942") => None,
+        #[feature = "rendering"]
         "dwGlowManager" => pattern!("488b05${'} c3 cccccccccccccccc 8b41") => None,
         "dwLocalPlayerController" => pattern!("488905${'} 8b9e") => None,
         "dwPlantedC4" => pattern!("488b15${'} 41ffc0") => None,
-        "dwPrediction" => pattern!("488d05${'} c3 cccccccccccccccc 4883ec? 8b0d") => Some(|_view, map, rva| {
-            map.insert("dwLocalPlayerPawn".to_string(), rva + 0x180);
+        "dwPrediction" => pattern!("488d05${'} c3 cccccccccccccccc 4883ec? 8b0d") => Some(|view, map, rva| {
+            let Some(displacement) = local_player_pawn_displacement(view, rva) else {
+                error!("outdated pattern: dwLocalPlayerPawn");
+
+                return;
+            };
+
+            if let Some(value) = checked_offset("dwLocalPlayerPawn", rva, displacement as i64) {
+                map.insert("dwLocalPlayerPawn".to_string(), value);
+            }
         }),
+        #[feature = "input"]
         "dwSensitivity" => pattern!("488d0d${[8]'} 440f28c1 0f28f3 0f28fa e8") => None,
-        "dwSensitivity_sensitivity" => pattern!("ff50u1 4c8bc6 488d55? 488bcf e8${} 84c0 0f85${} 4c8d45? 8bd3 488bcf e8${} e9${} f30f1006") => None,
+        #[feature = "input"]
+        #[signed]
+        "dwSensitivity_sensitivity" => pattern!("ff50i1 4c8bc6 488d55? 488bcf e8${} 84c0 0f85${} 4c8d45? 8bd3 488bcf e8${} e9${} f30f1006") => None,
+        // The view-projection matrix, which transforms world coordinates to clip space.
+        #[feature = "rendering"]
+        #[layout = "float[4][4] row-major"]
         "dwViewMatrix" => pattern!("488d0d${'} 48c1e006") => None,
+        #[feature = "rendering"]
         "dwViewRender" => pattern!("488905${'} 488bc8 4885c0") => None,
         "dwWeaponC4" => pattern!("488b15${'} 488b5c24? ffc0 8905[4] 488bc7") => None,
     },
     engine2 => {
+        #[target = "CEngineClient::GetEngineBuildNumber"]
         "dwBuildNumber" => pattern!("8905${'} 488d0d${} ff15${} 488b0d") => None,
+        #[signature = CREATE_INTERFACE]
+        "dwCreateInterface" => export!("CreateInterface") => Some(interface_regs),
+        #[feature = "networking"]
         "dwNetworkGameClient" => pattern!("48893d${'} 488d15") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_clientTickCount" => pattern!("8b81u4 c3 cccccccccccccccccc 8b81${} c3 cccccccccccccccccc 83b9") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_deltaTick" => pattern!("89b3u4 8b45") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_isBackgroundMap" => pattern!("0fb681u4 c3 cccccccccccccccc 0fb681${} c3 cccccccccccccccc 48895c24") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_localPlayer" => pattern!("4883c0u1 488d0440 8b0cc1") => Some(|_view, map, rva| {
             // .text 48 83 C0 0A | add rax, 0Ah
             // .text 48 8D 04 40 | lea rax, [rax + rax * 2]
             // .text 8B 0C C1    | mov ecx, [rcx + rax * 8]
-            map.insert("dwNetworkGameClient_localPlayer".to_string(), (rva + (rva * 2)) * 8);
+            let index = rva as i64;
+
+            let value = checked_delta("dwNetworkGameClient_localPlayer", (index + (index * 2)) * 8);
+
+            if let Some(value) = value {
+                map.insert("dwNetworkGameClient_localPlayer".to_string(), value);
+            }
         }),
+        #[feature = "networking"]
         "dwNetworkGameClient_maxClients" => pattern!("8b81u4 c3cccccccccccccccccc 8b81${} ffc0") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_serverTickCount" => pattern!("8b81u4 c3 cccccccccccccccccc 83b9") => None,
+        #[feature = "networking"]
         "dwNetworkGameClient_signOnState" => pattern!("448b81u4 488d0d") => None,
+        #[feature = "rendering"]
         "dwWindowHeight" => pattern!("8b05${'} 8903") => None,
+        #[feature = "rendering"]
         "dwWindowWidth" => pattern!("8b05${'} 8907") => None,
     },
     input_system => {
+        #[signature = CREATE_INTERFACE]
+        "dwCreateInterface" => export!("CreateInterface") => Some(interface_regs),
+        #[feature = "input"]
         "dwInputSystem" => pattern!("488905${'} 488d05") => None,
     },
     matchmaking => {
+        #[signature = CREATE_INTERFACE]
+        "dwCreateInterface" => export!("CreateInterface") => Some(interface_regs),
         "dwGameTypes" => pattern!("488d0d${'} 33d2") => None,
         "dwGameTypes_mapName" => pattern!("488b81u4 4885c074? 4883c0") => None,
     },
     soundsystem => {
+        #[signature = CREATE_INTERFACE]
+        "dwCreateInterface" => export!("CreateInterface") => Some(interface_regs),
         "dwSoundSystem" => pattern!("488d05${'} c3 cccccccccccccccc 488915") => None,
-        "dwSoundSystem_engineViewData" => pattern!("0f1147u1 0f104b") => None,
+        #[signed]
+        "dwSoundSystem_engineViewData" => pattern!("0f1147i1 0f104b") => None,
     },
 }
 
-pub fn offsets<P: Process + MemoryView>(process: &mut P) -> Result<OffsetMap> {
-    let mut map = BTreeMap::new();
+/// The start of `CreateInterface`, which loads `s_pInterfaceRegs` first, as [`interface_regs`]
+/// expects.
+const CREATE_INTERFACE: &[u8] = &[0x4C, 0x8B, 0x0D];
 
-    let modules: [(&str, fn(PeView) -> BTreeMap<String, u32>); 5] = [
-        ("client.dll", client::offsets),
-        ("engine2.dll", engine2::offsets),
-        ("inputsystem.dll", input_system::offsets),
-        ("matchmaking.dll", matchmaking::offsets),
-        ("soundsystem.dll", soundsystem::offsets),
-    ];
+/// Adds `dwInterfaceRegs`, the head of the `s_pInterfaceRegs` list that `CreateInterface` walks.
+fn interface_regs(view: &PeView, map: &mut BTreeMap<String, Rva>, rva: Rva) {
+    // .text 4C 8B 0D ?? ?? ?? ?? | mov r9, [rip + s_pInterfaceRegs]
+    let Ok(disp) = view.derva_copy::<i32>(rva + 3) else {
+        error!("outdated pattern: dwInterfaceRegs");
 
-    for (module_name, offsets) in &modules {
-        let module = process.module_by_name(module_name)?;
+        return;
+    };
 
-        let buf = process
-            .read_raw(module.base, module.size as _)
-            .data_part()?;
+    if let Some(value) = checked_offset("dwInterfaceRegs", rva + 7, disp as i64) {
+        map.insert("dwInterfaceRegs".to_string(), value);
+    }
+}
 
-        let view = PeView::from_bytes(&buf)?;
+/// Resolves the offsets of a module from its image.
+pub type Resolver = fn(PeView) -> Result<BTreeMap<String, Rva>, DumperError>;
 
-        map.insert(module_name.to_string(), offsets(view));
-    }
+/// Resolves the offsets of a module registered with [`Registry::register_module`] from its image,
+/// and the target of each offset that names one, such as the function or global it refers to,
+/// which is written to the generated files like `#[target]`.
+pub type Provider = fn(PeView) -> (BTreeMap<String, Rva>, BTreeMap<String, String>);
 
-    Ok(map)
+/// Modules resolved in addition to the built-in ones, which is passed to [`offsets_with_reader`]
+/// and the functions like it, so that only the scans it's passed to include them.
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    /// Modules registered with [`Registry::register_module`], resolved after the built-in ones.
+    providers: Vec<(String, Provider)>,
+    /// Callbacks registered with [`Registry::register_cross_module`], as
+    /// `(dll_name, depends_on, callback)`.
+    cross_module: Vec<(String, Vec<String>, CrossModuleCallback)>,
+    /// Modules loaded with [`Registry::load_module_list`], resolved after the registered ones.
+    listed: Vec<ListedModule>,
 }
 
-#[cfg(test)]
-mod tests {
-    use std::fs;
+impl Registry {
+    /// Registers an external provider for `dll_name`, so that its offsets are included in the
+    /// results like those of the built-in modules.
+    pub fn register_module(
+        &mut self,
+        dll_name: &str,
+        provider: Provider,
+    ) -> Result<(), DumperError> {
+        if self.module_names().contains(&dll_name) {
+            return Err(DumperError::DuplicateModule(dll_name.to_string()));
+        }
 
-    use serde_json::Value;
+        self.providers.push((dll_name.to_string(), provider));
 
-    use super::*;
+        Ok(())
+    }
 
-    fn setup() -> Result<IntoProcessInstanceArcBox<'static>> {
-        let os = memflow_native::create_os(&OsArgs::default(), LibArc::default())?;
+    /// Loads a list of additional DLLs to scan from a file, with one DLL name per line. Patterns
+    /// for a DLL are given as `vstdlib.dll:dwFoo=<pattern>`, which also lists the DLL. Blank lines
+    /// and lines starting with `#` are ignored.
+    pub fn load_module_list(&mut self, path: &Path) -> Result<(), DumperError> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| DumperError::ReadFailed(format!("{}: {}", path.display(), err)))?;
 
-        let process = os.into_process_by_name("cs2.exe")?;
+        for module in parse_module_list(&content)? {
+            if self.module_names().contains(&module.dll_name.as_str()) {
+                return Err(DumperError::DuplicateModule(module.dll_name));
+            }
 
-        Ok(process)
+            self.listed.push(module);
+        }
+
+        Ok(())
     }
 
-    fn get_class_field_value(module_name: &str, class_name: &str, field_name: &str) -> Option<u64> {
-        let content =
-            fs::read_to_string(format!("output/{}.json", module_name.replace(".", "_"))).ok()?;
+    /// Returns the names of the DLLs loaded with [`Registry::load_module_list`].
+    pub fn listed_modules(&self) -> Vec<&str> {
+        self.listed
+            .iter()
+            .map(|module| module.dll_name.as_str())
+            .collect()
+    }
 
-        let value: Value = serde_json::from_str(&content).ok()?;
+    /// Fails if any of the DLLs loaded with [`Registry::load_module_list`] isn't loaded according
+    /// to `is_loaded`, so that a typo in the list isn't only logged once scanning it fails.
+    pub fn check_listed_modules<F>(&self, mut is_loaded: F) -> Result<(), DumperError>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let missing: Vec<_> = self
+            .listed_modules()
+            .into_iter()
+            .filter(|dll_name| !is_loaded(dll_name))
+            .collect();
 
-        value
-            .get(module_name)?
-            .get("classes")?
-            .get(class_name)?
-            .get("fields")?
-            .get(field_name)?
-            .as_u64()
+        if !missing.is_empty() {
+            return Err(DumperError::ModuleNotFound(missing.join(", ")));
+        }
+
+        Ok(())
     }
 
-    fn get_offset_value(module_name: &str, offset_name: &str) -> Option<u64> {
-        let content = fs::read_to_string("output/offsets.json").ok()?;
-        let value: Value = serde_json::from_str(&content).ok()?;
+    /// Returns the names of all modules to scan, the built-in ones followed by the registered and
+    /// the listed ones.
+    pub fn module_names(&self) -> Vec<&str> {
+        module_names()
+            .into_iter()
+            .chain(self.providers.iter().map(|(dll_name, _)| dll_name.as_str()))
+            .chain(self.listed_modules())
+            .collect()
+    }
 
-        let offset = value.get(module_name)?.get(offset_name)?;
+    /// Returns the source of every pattern like [`patterns`], including those of listed modules.
+    pub fn patterns(&self) -> PatternMap {
+        let mut map = patterns();
 
-        offset.as_u64()
+        for module in &self.listed {
+            let sources = module
+                .patterns
+                .iter()
+                .map(|(name, _, source)| (name.clone(), source.clone()))
+                .collect();
+
+            map.insert(module.dll_name.clone(), sources);
+        }
+
+        map
     }
 
-    #[test]
-    fn build_number() -> Result<()> {
-        let mut process = setup()?;
+    fn provider(&self, dll_name: &str) -> Option<Provider> {
+        self.providers
+            .iter()
+            .find(|(name, _)| name == dll_name)
+            .map(|&(_, provider)| provider)
+    }
 
-        let engine_base = process.module_by_name("engine2.dll")?.base;
+    /// Registers `callback` to run once the patterns of `dll_name` have been resolved. The modules
+    /// in `depends_on` are resolved before `dll_name`, and their offsets are passed to the callback.
+    pub fn register_cross_module(
+        &mut self,
+        dll_name: &str,
+        depends_on: &[&str],
+        callback: CrossModuleCallback,
+    ) {
+        self.cross_module.push((
+            dll_name.to_string(),
+            depends_on.iter().map(|name| name.to_string()).collect(),
+            callback,
+        ));
+    }
 
-        let offset = get_offset_value("engine2.dll", "dwBuildNumber").unwrap();
+    /// Orders `modules` so that each module comes after the modules its cross-module callbacks
+    /// depend on. Otherwise, the order is kept. Dependencies that aren't in `modules` are ignored.
+    fn resolution_order<'a>(&self, modules: &[&'a str]) -> Result<Vec<&'a str>, DumperError> {
+        let mut pending = modules.to_vec();
+        let mut order = Vec::with_capacity(modules.len());
 
-        let build_number: u32 = process.read(engine_base + offset).data_part()?;
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|&module_name| {
+                self.cross_module
+                    .iter()
+                    .filter(|(dll_name, ..)| dll_name == module_name)
+                    .flat_map(|(_, depends_on, _)| depends_on.iter())
+                    .all(|dependency| !pending.contains(&dependency.as_str()))
+            });
 
-        println!("build number: {}", build_number);
+            let Some(i) = ready else {
+                return Err(DumperError::DependencyCycle(
+                    pending.iter().map(|name| name.to_string()).collect(),
+                ));
+            };
 
-        Ok(())
+            order.push(pending.remove(i));
+        }
+
+        Ok(order)
     }
 
-    #[test]
-    fn global_vars() -> Result<()> {
-        let mut process = setup()?;
+    /// Runs the cross-module callbacks of `module_name` with the offsets resolved so far.
+    fn run_cross_module(
+        &self,
+        module_name: &str,
+        view: &PeView<'_>,
+        offsets: &mut BTreeMap<String, Rva>,
+        resolved: &OffsetMap,
+    ) {
+        for &(_, _, callback) in self
+            .cross_module
+            .iter()
+            .filter(|(dll_name, ..)| dll_name == module_name)
+        {
+            callback(view, offsets, resolved);
+        }
+    }
+}
 
-        let client_base = process.module_by_name("client.dll")?.base;
+/// A callback that adds to the offsets of a module, using the offsets already resolved for the
+/// modules it depends on.
+pub type CrossModuleCallback = fn(&PeView, &mut BTreeMap<String, Rva>, &OffsetMap);
 
-        let offset = get_offset_value("client.dll", "dwGlobalVars").unwrap();
+#[rustfmt::skip]
+const MODULES: [(&str, &str, &Patterns, Resolver); 5] = [
+    ("client", "client.dll", &client::PATTERNS, client::offsets),
+    ("engine2", "engine2.dll", &engine2::PATTERNS, engine2::offsets),
+    ("input_system", "inputsystem.dll", &input_system::PATTERNS, input_system::offsets),
+    ("matchmaking", "matchmaking.dll", &matchmaking::PATTERNS, matchmaking::offsets),
+    ("soundsystem", "soundsystem.dll", &soundsystem::PATTERNS, soundsystem::offsets),
+];
 
-        let global_vars: u64 = process.read(client_base + offset).data_part()?;
+static DLL_NAMES: OnceLock<DllNames> = OnceLock::new();
 
-        let cur_map_name = {
-            let addr = process
-                .read_addr64((global_vars + 0x180).into())
-                .data_part()?;
+static PATTERN_OVERRIDES: OnceLock<Vec<PatternOverride>> = OnceLock::new();
 
-            process.read_utf8(addr, 128).data_part()?
+/// The offset that each offset inserted by a callback was derived from, by module and offset name.
+static DERIVED: Mutex<BTreeMap<(String, String), String>> = Mutex::new(BTreeMap::new());
+
+/// The offsets whose values are signed deltas, stored as the bits of an `i32`, by module and offset
+/// name. See [`is_signed`].
+static SIGNED: Mutex<BTreeSet<(String, String)>> = Mutex::new(BTreeSet::new());
+
+thread_local! {
+    /// The offsets that a callback set to negative deltas with [`checked_delta`], until the module
+    /// that ran the callback records them in [`SIGNED`].
+    static PENDING_SIGNED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The confidence of every offset resolved from a pattern, by module and offset name.
+static CONFIDENCE: Mutex<BTreeMap<(String, String), Confidence>> = Mutex::new(BTreeMap::new());
+
+/// The offsets whose callback failed, by module and offset name. See [`failed_callbacks`].
+static FAILED_CALLBACKS: Mutex<BTreeSet<(String, String)>> = Mutex::new(BTreeSet::new());
+
+/// The targets of the offsets of registered modules, by module and offset name. See [`target`].
+static TARGETS: Mutex<BTreeMap<(String, String), String>> = Mutex::new(BTreeMap::new());
+
+/// Whether to log every capture slot of each pattern match, set with [`set_debug_captures`].
+static DEBUG_CAPTURES: AtomicBool = AtomicBool::new(false);
+
+fn parse_module_list(content: &str) -> Result<Vec<ListedModule>, DumperError> {
+    let mut modules: Vec<ListedModule> = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (dll_name, pattern) = match line.split_once(':') {
+            Some((dll_name, rest)) => {
+                let Some((name, source)) = rest.split_once('=') else {
+                    return Err(DumperError::InvalidConfig(format!(
+                        "expected dll:name=<pattern> on line {}: {}",
+                        i + 1,
+                        line
+                    )));
+                };
+
+                (dll_name.trim(), Some((name.trim(), source.trim())))
+            }
+            None => (line, None),
         };
 
-        println!("current map name: {}", cur_map_name);
+        if dll_name.is_empty() {
+            return Err(DumperError::InvalidConfig(format!(
+                "empty DLL name on line {}",
+                i + 1
+            )));
+        }
 
-        Ok(())
+        let index = match modules
+            .iter()
+            .position(|module| module.dll_name == dll_name)
+        {
+            Some(index) => index,
+            None => {
+                modules.push(ListedModule {
+                    dll_name: dll_name.to_string(),
+                    patterns: Vec::new(),
+                });
+
+                modules.len() - 1
+            }
+        };
+
+        let Some((name, source)) = pattern else {
+            continue;
+        };
+
+        let module = &mut modules[index];
+
+        if module.patterns.iter().any(|(other, ..)| other == name) {
+            return Err(DumperError::InvalidConfig(format!(
+                "duplicate pattern on line {}: {}:{}",
+                i + 1,
+                dll_name,
+                name
+            )));
+        }
+
+        let pattern = CompiledPattern::parse(source).map_err(|err| {
+            DumperError::InvalidPattern(format!("{}:{} on line {}: {}", dll_name, name, i + 1, err))
+        })?;
+
+        module
+            .patterns
+            .push((name.to_string(), pattern, source.to_string()));
     }
 
-    #[test]
-    fn local_player_controller() -> Result<()> {
-        let mut process = setup()?;
+    Ok(modules)
+}
 
-        let client_base = process.module_by_name("client.dll")?.base;
+/// Resolves the patterns of a module loaded with [`Registry::load_module_list`]. Patterns that don't
+/// match are logged and left out, like those in `pattern_map!`.
+fn listed_offsets(view: &PeView<'_>, module: &ListedModule) -> BTreeMap<String, Rva> {
+    module
+        .patterns
+        .iter()
+        .filter_map(|(name, pat, _)| {
+            // Listed patterns can't be exports, so they don't need their source.
+            let (rva, confidence) = resolve_entry(view, name, pat, &PatternAttrs::DEFAULT)?;
 
-        let local_player_controller_offset =
-            get_offset_value("client.dll", "dwLocalPlayerController").unwrap();
+            record_confidence(&module.dll_name, name, confidence);
 
-        let player_name_offset =
-            get_class_field_value("client.dll", "CBasePlayerController", "m_iszPlayerName")
-                .unwrap();
+            Some((name.clone(), rva))
+        })
+        .collect()
+}
 
-        let local_player_controller: u64 = process
-            .read(client_base + local_player_controller_offset)
-            .data_part()?;
+/// Logs the contents of every capture slot of each pattern match, not only the resolved one, to
+/// debug patterns with multiple captures.
+pub fn set_debug_captures(enabled: bool) {
+    DEBUG_CAPTURES.store(enabled, Ordering::Relaxed);
+}
 
-        let player_name = process
-            .read_utf8((local_player_controller + player_name_offset).into(), 4096)
-            .data_part()?;
+/// Reads the displacement of the local player pawn within the prediction object at `prediction`
+/// from code that loads it, such as `lea rcx, [rip + prediction]; mov rax, [rcx + 0x180]`.
+fn local_player_pawn_displacement(view: &PeView<'_>, prediction: Rva) -> Option<u32> {
+    let mut save = [0; 3];
+    let mut matches = view.scanner().matches_code(pattern!("488d? ${'} 488b? u4"));
 
-        println!("local player name: {}", player_name);
+    while matches.next(&mut save) {
+        let Ok(code) = view.derva_slice::<u8>(save[0], 10) else {
+            continue;
+        };
 
-        Ok(())
+        if !loads_from_lea(code[2], code[9]) {
+            continue;
+        }
+
+        // Only accept a `lea` of the prediction object, and a small displacement, since anything
+        // else means the `mov` belongs to different code.
+        if save[1] == prediction && save[2] != 0 && save[2] <= 0x10000 {
+            return Some(save[2]);
+        }
     }
 
-    #[test]
-    fn local_player_pawn() -> Result<()> {
-        #[derive(Debug, Pod)]
-        #[repr(C)]
-        struct Vector3D {
-            x: f32,
-            y: f32,
-            z: f32,
+    None
+}
+
+/// Returns whether `lea` and `mov` are the ModRM bytes of `lea reg, [rip + disp32]` followed by
+/// `mov r64, [reg + disp32]`, so that the `mov` reads a field of what the `lea` loaded.
+fn loads_from_lea(lea: u8, mov: u8) -> bool {
+    // mod = 00 with rm = 101 is RIP-relative.
+    let rip_relative = lea & 0xC7 == 0x05;
+
+    // mod = 10 is a 32-bit displacement, and rm = 100 a SIB byte instead of a base register.
+    let displacement = mov >> 6 == 0b10 && mov & 0x07 != 0b100;
+
+    rip_relative && displacement && (lea >> 3) & 0x07 == mov & 0x07
+}
+
+/// Formats capture slots as `[0] 0x1000, [1] 0x10`. Slot 0 holds the start of the match.
+fn format_captures(save: &[Rva]) -> String {
+    save.iter()
+        .enumerate()
+        .map(|(i, value)| format!("[{}] {:#X}", i, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Loads DLL name overrides from a JSON file such as `{"engine2": "engine2_new.dll"}`. Must be
+/// called before any offsets are resolved.
+pub fn load_dll_names(path: &Path) -> Result<(), DumperError> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| DumperError::ReadFailed(format!("{}: {}", path.display(), err)))?;
+
+    let names: DllNames = serde_json::from_str(&content).map_err(|err| {
+        DumperError::InvalidConfig(format!("invalid DLL names in {}: {}", path.display(), err))
+    })?;
+
+    validate_dll_names(&names)?;
+
+    DLL_NAMES
+        .set(names)
+        .map_err(|_| DumperError::AlreadyLoaded("DLL names"))
+}
+
+fn validate_dll_names(names: &DllNames) -> Result<(), DumperError> {
+    for (module, dll_name) in names {
+        if !MODULES.iter().any(|(name, ..)| *name == module.as_str()) {
+            return Err(DumperError::InvalidConfig(format!(
+                "unknown module in DLL names: {}",
+                module
+            )));
         }
 
-        let mut process = setup()?;
+        if dll_name.is_empty() {
+            return Err(DumperError::InvalidConfig(format!(
+                "empty DLL name for module: {}",
+                module
+            )));
+        }
+    }
 
-        let client_base = process.module_by_name("client.dll")?.base;
+    let modules = modules_with(names);
 
-        let local_player_pawn_offset = get_offset_value("client.dll", "dwLocalPlayerPawn").unwrap();
+    for (i, (dll_name, ..)) in modules.iter().enumerate() {
+        if modules[..i].iter().any(|(other, ..)| other == dll_name) {
+            return Err(DumperError::InvalidConfig(format!(
+                "DLL used by multiple modules: {}",
+                dll_name
+            )));
+        }
+    }
 
-        let game_scene_node_offset =
-            get_class_field_value("client.dll", "C_BaseEntity", "m_pGameSceneNode").unwrap();
+    Ok(())
+}
 
-        let vec_abs_origin_offset =
-            get_class_field_value("client.dll", "CGameSceneNode", "m_vecAbsOrigin").unwrap();
+/// Parses and validates overrides such as `client:dwEntityList=488935${'} 4885f6`, which replace
+/// the built-in patterns when resolving offsets. Must be called before any offsets are resolved.
+pub fn load_pattern_overrides(args: &[String]) -> Result<(), DumperError> {
+    let overrides = args
+        .iter()
+        .map(|arg| parse_pattern_override(arg))
+        .collect::<Result<Vec<_>, _>>()?;
 
-        let local_player_pawn: u64 = process
-            .read(client_base + local_player_pawn_offset)
-            .data_part()?;
+    PATTERN_OVERRIDES
+        .set(overrides)
+        .map_err(|_| DumperError::AlreadyLoaded("pattern overrides"))
+}
 
-        let game_scene_node: u64 = process
-            .read((local_player_pawn + game_scene_node_offset).into())
-            .data_part()?;
+fn parse_pattern_override(arg: &str) -> Result<PatternOverride, DumperError> {
+    let Some((module, name, source)) = arg.split_once(':').and_then(|(module, rest)| {
+        rest.split_once('=')
+            .map(|(name, source)| (module, name, source))
+    }) else {
+        return Err(DumperError::InvalidConfig(format!(
+            "expected module:name=<pattern>: {}",
+            arg
+        )));
+    };
 
-        let vec_abs_origin: Vector3D = process
-            .read((game_scene_node + vec_abs_origin_offset).into())
-            .data_part()?;
+    let Some((_, _, patterns, _)) = MODULES.iter().find(|(other, ..)| *other == module) else {
+        return Err(DumperError::InvalidConfig(format!(
+            "unknown module in pattern override: {}",
+            module
+        )));
+    };
+
+    let Some((_, _, attrs)) = patterns.get(name) else {
+        return Err(DumperError::InvalidConfig(format!(
+            "unknown offset in pattern override: {}:{}",
+            module, name
+        )));
+    };
+
+    if attrs.export {
+        return Err(DumperError::InvalidConfig(format!(
+            "{}:{} is resolved from an export, not a pattern",
+            module, name
+        )));
+    }
+
+    let pattern = CompiledPattern::parse(source)
+        .map_err(|err| DumperError::InvalidPattern(format!("{}:{}: {}", module, name, err)))?;
+
+    Ok(PatternOverride {
+        module: module.to_string(),
+        name: name.to_string(),
+        pattern,
+        // Overrides live for the rest of the run, like the built-in patterns.
+        source: Box::leak(source.to_string().into_boxed_str()),
+    })
+}
+
+fn pattern_override(module: &str, name: &str) -> Option<&'static CompiledPattern> {
+    PATTERN_OVERRIDES
+        .get()?
+        .iter()
+        .find(|entry| entry.module == module && entry.name == name)
+        .map(|entry| &entry.pattern)
+}
+
+/// Returns the offsets that the callback of `name` changed, as `(name, old, new)`. Callbacks may
+/// adjust the offset of their own entry, but other existing offsets are left alone.
+fn overwritten_offsets(
+    name: &str,
+    before: &BTreeMap<String, Rva>,
+    after: &BTreeMap<String, Rva>,
+) -> Vec<(String, Rva, Rva)> {
+    before
+        .iter()
+        .filter(|(key, _)| key.as_str() != name)
+        .filter_map(|(key, &old)| match after.get(key) {
+            Some(&new) if new != old => Some((key.clone(), old, new)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn record_derived<'a>(module_name: &str, parent: &str, names: impl Iterator<Item = &'a String>) {
+    let mut derived = DERIVED.lock().unwrap();
+
+    for name in names {
+        derived.insert((module_name.to_string(), name.clone()), parent.to_string());
+    }
+}
+
+fn record_confidence(module_name: &str, name: &str, confidence: Confidence) {
+    CONFIDENCE
+        .lock()
+        .unwrap()
+        .insert((module_name.to_string(), name.to_string()), confidence);
+}
+
+/// Returns the confidence of `name` in `module_name`, or `None` if it wasn't resolved from a
+/// pattern, such as offsets inserted by callbacks.
+pub fn confidence(module_name: &str, name: &str) -> Option<Confidence> {
+    CONFIDENCE
+        .lock()
+        .unwrap()
+        .get(&(module_name.to_string(), name.to_string()))
+        .copied()
+}
+
+fn record_targets(module_name: &str, targets: BTreeMap<String, String>) {
+    let mut map = TARGETS.lock().unwrap();
+
+    for (name, target) in targets {
+        map.insert((module_name.to_string(), name), target);
+    }
+}
+
+/// Returns the target of `name` in `module_name`, such as the function or global it refers to,
+/// from its `#[target]` attribute or the provider of a registered module, or `None` if it has none.
+pub fn target(module_name: &str, name: &str) -> Option<String> {
+    pattern_attrs(module_name, name)
+        .map(|attrs| attrs.target)
+        .filter(|target| !target.is_empty())
+        .map(str::to_string)
+        .or_else(|| {
+            TARGETS
+                .lock()
+                .unwrap()
+                .get(&(module_name.to_string(), name.to_string()))
+                .cloned()
+        })
+}
+
+fn record_signed(module_name: &str, name: &str) {
+    SIGNED
+        .lock()
+        .unwrap()
+        .insert((module_name.to_string(), name.to_string()));
+}
+
+/// Returns whether the value of `name` in `module_name` is a signed delta, such as a negative field
+/// offset, which is stored as the bits of an `i32`. See [`offset_value`].
+pub fn is_signed(module_name: &str, name: &str) -> bool {
+    pattern_attrs(module_name, name).is_some_and(|attrs| attrs.signed)
+        || SIGNED
+            .lock()
+            .unwrap()
+            .contains(&(module_name.to_string(), name.to_string()))
+}
+
+/// Returns the value of `name` in `module_name` as stored in `value`, sign-extended if the offset
+/// is signed, for the generated files.
+pub fn offset_value(module_name: &str, name: &str, value: Rva) -> i64 {
+    if is_signed(module_name, name) {
+        value as i32 as i64
+    } else {
+        value as i64
+    }
+}
+
+fn record_failed_callback(module_name: &str, name: &str) {
+    FAILED_CALLBACKS
+        .lock()
+        .unwrap()
+        .insert((module_name.to_string(), name.to_string()));
+}
+
+/// Returns the offsets whose pattern matched but whose callback failed, such as `dwCSGOInput` if
+/// `dwViewAngles` couldn't be derived from it. Those offsets are still resolved, so this tells a
+/// broken callback apart from a broken pattern.
+pub fn failed_callbacks() -> FailedCallbacks {
+    let mut map = FailedCallbacks::new();
+
+    for (module_name, name) in FAILED_CALLBACKS.lock().unwrap().iter() {
+        map.entry(module_name.clone())
+            .or_default()
+            .insert(name.clone());
+    }
+
+    map
+}
+
+/// Returns whether an expression from [`expressions`] is that of an address in its module, relative
+/// to the module base or to another address such as `dwPrediction + 0x180`, rather than that of a
+/// constant or of a field relative to a dereferenced offset.
+pub fn is_address(expression: &str) -> bool {
+    !expression.starts_with('[') && (expression.contains(" + ") || expression.contains(" - "))
+}
+
+/// Returns how each offset is calculated. Offsets inserted by callbacks are relative to the offset
+/// they were derived from, fields such as `dwGameTypes_mapName` to the value of the offset they
+/// belong to, and all others to the module base.
+pub fn expressions(offsets: &OffsetMap) -> ExpressionMap {
+    let derived = DERIVED.lock().unwrap();
+
+    offsets
+        .iter()
+        .map(|(module_name, offsets)| {
+            let expressions = offsets
+                .iter()
+                .map(|(name, &value)| {
+                    if is_signed(module_name, name) {
+                        let owner = derived
+                            .get(&(module_name.clone(), name.clone()))
+                            .map(String::as_str)
+                            .or_else(|| {
+                                name.split_once('_')
+                                    .map(|(owner, _)| owner)
+                                    .filter(|owner| offsets.contains_key(*owner))
+                            });
+
+                        let value = offset_value(module_name, name, value);
+                        let sign = if value < 0 { "-" } else { "+" };
+
+                        let expression = match owner {
+                            Some(owner) => {
+                                format!("[{}] {} {:#X}", owner, sign, value.unsigned_abs())
+                            }
+                            None if value < 0 => format!("-{:#X}", value.unsigned_abs()),
+                            None => format!("{:#X}", value),
+                        };
+
+                        return (name.clone(), expression);
+                    }
+
+                    let parent = derived
+                        .get(&(module_name.clone(), name.clone()))
+                        .and_then(|parent| Some((parent, *offsets.get(parent)?)));
+
+                    let expression = match (parent, name.split_once('_')) {
+                        (Some((parent, base)), _) if value >= base => {
+                            format!("{} + {:#X}", parent, value - base)
+                        }
+                        (Some((parent, base)), _) => format!("{} - {:#X}", parent, base - value),
+                        (None, _) if is_constant(module_name, name) => format!("{:#X}", value),
+                        (None, Some((owner, _))) if offsets.contains_key(owner) => {
+                            format!("[{}] + {:#X}", owner, value)
+                        }
+                        (None, _) => format!("{} + {:#X}", module_name, value),
+                    };
+
+                    (name.clone(), expression)
+                })
+                .collect();
+
+            (module_name.clone(), expressions)
+        })
+        .collect()
+}
+
+/// Returns the DLL that the `pattern_map!` module `module` is resolved in.
+fn dll_name(module: &str) -> Option<&'static str> {
+    let names = DLL_NAMES.get_or_init(DllNames::new);
+
+    MODULES
+        .iter()
+        .find(|(name, ..)| *name == module)
+        .map(|&(module, dll_name, ..)| names.get(module).map_or(dll_name, String::as_str))
+}
+
+/// Returns the DLL name, patterns and resolver of every module, with the DLL names overridden by
+/// `names`.
+fn modules_with(names: &DllNames) -> Vec<(&str, &'static Patterns, Resolver)> {
+    MODULES
+        .iter()
+        .map(|&(module, dll_name, patterns, offsets)| {
+            let dll_name = names.get(module).map_or(dll_name, String::as_str);
+
+            (dll_name, patterns, offsets)
+        })
+        .collect()
+}
+
+fn modules() -> Vec<(&'static str, &'static Patterns, Resolver)> {
+    modules_with(DLL_NAMES.get_or_init(DllNames::new))
+}
+
+/// Returns every offset the dumper knows how to resolve as `(module, name, pattern)`, without
+/// scanning anything. Offsets inserted by callbacks aren't included.
+pub fn catalog() -> Vec<(&'static str, &'static str, &'static [Atom])> {
+    let mut entries: Vec<_> = modules()
+        .into_iter()
+        .flat_map(|(module_name, patterns, _)| {
+            patterns
+                .entries()
+                .map(move |(&name, (pat, ..))| (module_name, name, *pat))
+        })
+        .collect();
+
+    entries.sort_by_key(|&(module_name, name, _)| (module_name, name));
+
+    entries
+}
+
+/// Returns the source of every pattern, by module and offset name, including overrides. The
+/// patterns of listed modules are included by [`Registry::patterns`].
+pub fn patterns() -> PatternMap {
+    let mut map = modules()
+        .into_iter()
+        .map(|(module_name, patterns, _)| {
+            let sources = patterns
+                .entries()
+                .map(|(&name, (_, _, attrs))| (name.to_string(), attrs.source.to_string()))
+                .collect();
+
+            (module_name.to_string(), sources)
+        })
+        .collect();
+
+    if let Some(overrides) = PATTERN_OVERRIDES.get() {
+        apply_overrides(&mut map, overrides);
+    }
+
+    map
+}
+
+fn apply_overrides(map: &mut PatternMap, overrides: &[PatternOverride]) {
+    for entry in overrides {
+        let Some(dll_name) = dll_name(&entry.module) else {
+            continue;
+        };
+
+        if let Some(source) = map
+            .get_mut(dll_name)
+            .and_then(|sources| sources.get_mut(&entry.name))
+        {
+            *source = entry.source.to_string();
+        }
+    }
+}
+
+/// Returns only the offsets whose pattern is tagged with `feature`. Offsets inserted by callbacks
+/// don't have a feature and are left out, as are modules without any remaining offsets.
+pub fn filter_by_feature(offsets: &OffsetMap, feature: &str) -> OffsetMap {
+    offsets
+        .iter()
+        .map(|(module_name, offsets)| {
+            let offsets: BTreeMap<_, _> = offsets
+                .iter()
+                .filter(|(name, _)| {
+                    pattern_attrs(module_name, name).is_some_and(|attrs| attrs.feature == feature)
+                })
+                .map(|(name, value)| (name.clone(), *value))
+                .collect();
+
+            (module_name.clone(), offsets)
+        })
+        .filter(|(_, offsets)| !offsets.is_empty())
+        .collect()
+}
+
+/// Checks that each of `globs` is a valid offset name, optionally with `*` (any number of
+/// characters) and `?` (a single character) wildcards.
+pub fn validate_globs(globs: &[String]) -> Result<()> {
+    for glob in globs {
+        if glob.is_empty() {
+            bail!("empty offset name pattern");
+        }
+
+        if let Some(c) = glob
+            .chars()
+            .find(|&c| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '*' | '?')))
+        {
+            bail!("invalid character {:?} in offset name pattern: {}", c, glob);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the offsets whose names don't match any of `globs`, such as `dwSensitivity*`.
+pub fn exclude_offsets(offsets: &OffsetMap, globs: &[String]) -> OffsetMap {
+    offsets
+        .iter()
+        .map(|(module_name, offsets)| {
+            let offsets = offsets
+                .iter()
+                .filter(|(name, _)| !globs.iter().any(|glob| glob_matches(glob, name)))
+                .map(|(name, value)| (name.clone(), *value))
+                .collect();
+
+            (module_name.clone(), offsets)
+        })
+        .collect()
+}
+
+/// Checks that each of `names`, given as `module:name` such as `client.dll:dwEntityList`, is in
+/// `offsets`, for tests that depend on a set of offsets. The error lists all missing ones.
+pub fn assert_offsets_present(offsets: &OffsetMap, names: &[&str]) -> Result<()> {
+    let mut missing = Vec::new();
+
+    for &qualified in names {
+        let (module_name, name) = qualified
+            .split_once(':')
+            .with_context(|| format!("expected module:name, got {}", qualified))?;
+
+        if !offsets
+            .get(module_name)
+            .is_some_and(|offsets| offsets.contains_key(name))
+        {
+            missing.push(qualified);
+        }
+    }
+
+    if !missing.is_empty() {
+        bail!(
+            "{} of {} offsets are missing: {}",
+            missing.len(),
+            names.len(),
+            missing.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// A change to the value of an offset in the generated files, for consumers that store offsets
+/// differently, such as as an index of pointers rather than a byte offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    Identity,
+    /// Divides the value, such as by `8` for an index of 64-bit pointers.
+    Div(Rva),
+    /// Keeps only the bits of the value that are set in the mask.
+    And(Rva),
+}
+
+impl Transform {
+    pub fn apply(self, value: Rva) -> Rva {
+        match self {
+            Self::Identity => value,
+            Self::Div(divisor) => value / divisor,
+            Self::And(mask) => value & mask,
+        }
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Identity => write!(f, "identity"),
+            Self::Div(divisor) => write!(f, "div:{}", divisor),
+            Self::And(mask) => write!(f, "and:{:#X}", mask),
+        }
+    }
+}
+
+/// The transforms to apply to offsets in the generated files, by module and offset name.
+pub type TransformMap = BTreeMap<String, BTreeMap<String, Transform>>;
+
+/// Parses transforms such as `client.dll:dwEntityList=div:8`. The supported transforms are
+/// `identity`, `div:<divisor>` and `and:<mask>`, with decimal or `0x`-prefixed hex numbers.
+pub fn parse_transforms(args: &[String]) -> Result<TransformMap> {
+    let mut map = TransformMap::new();
+
+    for arg in args {
+        let Some((module_name, name, transform)) =
+            arg.split_once(':').and_then(|(module_name, rest)| {
+                rest.split_once('=')
+                    .map(|(name, transform)| (module_name, name, transform))
+            })
+        else {
+            bail!("expected module:name=<transform>: {}", arg);
+        };
+
+        let parse_number = |s: &str| match s.strip_prefix("0x") {
+            Some(hex) => Rva::from_str_radix(hex, 16),
+            None => s.parse(),
+        };
+
+        let transform = match transform.split_once(':') {
+            None if transform == "identity" => Transform::Identity,
+            Some(("div", divisor)) => match parse_number(divisor) {
+                Ok(0) => bail!("division by zero in transform: {}", arg),
+                Ok(divisor) => Transform::Div(divisor),
+                Err(err) => bail!("invalid divisor in transform {}: {}", arg, err),
+            },
+            Some(("and", mask)) => Transform::And(
+                parse_number(mask)
+                    .with_context(|| format!("invalid mask in transform: {}", arg))?,
+            ),
+            _ => bail!(
+                "unknown transform (expected identity, div:<n> or and:<mask>): {}",
+                arg
+            ),
+        };
+
+        map.entry(module_name.to_string())
+            .or_default()
+            .insert(name.to_string(), transform);
+    }
+
+    Ok(map)
+}
+
+/// Returns `offsets` with the values of the offsets in `transforms` transformed. Warns about values
+/// that aren't a multiple of their divisor, since the remainder is lost.
+pub fn apply_transforms(offsets: &OffsetMap, transforms: &TransformMap) -> OffsetMap {
+    offsets
+        .iter()
+        .map(|(module_name, offsets)| {
+            let offsets = offsets
+                .iter()
+                .map(|(name, &value)| {
+                    let Some(&transform) = transforms
+                        .get(module_name)
+                        .and_then(|transforms| transforms.get(name))
+                    else {
+                        return (name.clone(), value);
+                    };
+
+                    if matches!(transform, Transform::Div(divisor) if value % divisor != 0) {
+                        warn!(
+                            "{}:{} ({:#X}) isn't evenly divisible by its transform ({})",
+                            module_name, name, value, transform
+                        );
+                    }
+
+                    (name.clone(), transform.apply(value))
+                })
+                .collect();
+
+            (module_name.clone(), offsets)
+        })
+        .collect()
+}
+
+fn glob_matches(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut g, mut n) = (0, 0);
+
+    // The positions to resume from after the last `*`, if the rest doesn't match.
+    let mut star = None;
+
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_g, star_n)) => {
+                    star = Some((star_g, star_n + 1));
+                    g = star_g + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// Values that are compiled into the game's code rather than resolved, as `(module, name, value)`.
+/// They have to be updated by hand if the game changes them.
+#[rustfmt::skip]
+const CONSTANTS: [(&str, &str, Rva); 1] = [
+    // The number of entity identities per chunk of the entity list, which is indexed as
+    // `entity_list + 0x10 + 8 * (index / chunk_size)`, then `chunk + stride * (index % chunk_size)`.
+    ("client", "dwEntityList_chunkSize", 0x200),
+];
+
+/// Adds the entries of [`CONSTANTS`] to the modules that have been resolved.
+pub fn add_constants(offsets: &mut OffsetMap) {
+    for (module, name, value) in CONSTANTS {
+        let Some(offsets) = dll_name(module).and_then(|dll_name| offsets.get_mut(dll_name)) else {
+            continue;
+        };
+
+        offsets.insert(name.to_string(), value);
+    }
+}
+
+fn is_constant(module_name: &str, name: &str) -> bool {
+    CONSTANTS
+        .iter()
+        .any(|&(module, other, _)| other == name && dll_name(module) == Some(module_name))
+}
+
+/// Adds `dwCVar` to the offsets of `tier0.dll`, pointing at the instance of the cvar system. It's
+/// taken from the `VEngineCvar` interface, since there's no pattern for it.
+pub fn add_cvar_offset(offsets: &mut OffsetMap, interfaces: &InterfaceMap) {
+    let value = interfaces.get("tier0.dll").and_then(|ifaces| {
+        ifaces
+            .iter()
+            .find(|(name, _)| name.starts_with("VEngineCvar"))
+            .map(|(_, value)| *value)
+    });
+
+    let Some(value) = value else {
+        warn!("unable to find the cvar system interface");
+
+        return;
+    };
+
+    offsets
+        .entry("tier0.dll".to_string())
+        .or_default()
+        .insert("dwCVar".to_string(), value as Rva);
+}
+
+/// Returns the names of the built-in modules. Registered and listed modules are included by
+/// [`Registry::module_names`].
+pub fn module_names() -> Vec<&'static str> {
+    modules()
+        .into_iter()
+        .map(|(module_name, ..)| module_name)
+        .collect()
+}
+
+pub fn offsets<P: Process + MemoryView>(
+    process: &mut P,
+    registry: &Registry,
+    strict: bool,
+) -> Result<OffsetMap, DumperError> {
+    offsets_incremental(process, registry, strict, |_, _| {})
+}
+
+/// Like [`offsets`], but passes the offsets of each module to `sink` as soon as it has been
+/// resolved, before moving on to the next one.
+pub fn offsets_incremental<P, S>(
+    process: &mut P,
+    registry: &Registry,
+    strict: bool,
+    sink: S,
+) -> Result<OffsetMap, DumperError>
+where
+    P: Process + MemoryView,
+    S: FnMut(&str, &BTreeMap<String, Rva>),
+{
+    let reader = |module_name: &str| {
+        let module = process
+            .module_by_name(module_name)
+            .map_err(|_| DumperError::ModuleNotFound(module_name.to_string()))?;
+
+        read_module(process, &module)
+    };
+
+    offsets_with_sink(registry, &registry.module_names(), strict, reader, sink)
+}
+
+/// Resolves the offsets of `modules`, using `reader` to obtain the image bytes of each module.
+///
+/// Modules that fail to be read or analyzed are logged and left out of the result, unless `strict`
+/// is set, in which case the first failure is returned.
+pub fn offsets_with_reader<R>(
+    registry: &Registry,
+    modules: &[&str],
+    strict: bool,
+    reader: R,
+) -> Result<OffsetMap, DumperError>
+where
+    R: FnMut(&str) -> Result<Vec<u8>, DumperError>,
+{
+    offsets_with_sink(registry, modules, strict, reader, |_, _| {})
+}
+
+/// Like [`offsets_with_reader`], but passes the offsets of each module to `sink` as soon as it has
+/// been resolved. Modules are resolved in the order of `modules`, except that the dependencies of
+/// cross-module callbacks are resolved first.
+pub fn offsets_with_sink<R, S>(
+    registry: &Registry,
+    modules: &[&str],
+    strict: bool,
+    mut reader: R,
+    mut sink: S,
+) -> Result<OffsetMap, DumperError>
+where
+    R: FnMut(&str) -> Result<Vec<u8>, DumperError>,
+    S: FnMut(&str, &BTreeMap<String, Rva>),
+{
+    let mut map = BTreeMap::new();
+
+    for module_name in registry.resolution_order(modules)? {
+        let resolver = modules()
+            .into_iter()
+            .find(|(name, ..)| *name == module_name)
+            .map(|(_, _, offsets)| offsets);
+
+        let listed = registry
+            .listed
+            .iter()
+            .find(|module| module.dll_name == module_name);
+
+        let provider = registry.provider(module_name);
+
+        if resolver.is_none() && listed.is_none() && provider.is_none() {
+            return Err(DumperError::UnknownModule(module_name.to_string()));
+        }
+
+        let result = reader(module_name).and_then(|buf| {
+            let view = PeView::from_bytes(&buf)
+                .map_err(|source| DumperError::invalid_image(module_name, &buf, source))?;
+
+            let mut offsets = match (&listed, resolver, provider) {
+                (Some(module), ..) => listed_offsets(&view, module),
+                (None, Some(offsets), _) => offsets(view)?,
+                (None, None, Some(provider)) => {
+                    let (offsets, targets) = provider(view);
+
+                    record_targets(module_name, targets);
+
+                    offsets
+                }
+                (None, None, None) => unreachable!(),
+            };
+
+            registry.run_cross_module(module_name, &view, &mut offsets, &map);
+
+            Ok(offsets)
+        });
+
+        match result {
+            Ok(offsets) => {
+                sink(module_name, &offsets);
+
+                map.insert(module_name.to_string(), offsets);
+            }
+            Err(err) if strict => return Err(err),
+            Err(err) => error!("failed to read offsets of {}: {}", module_name, err),
+        }
+    }
+
+    Ok(map)
+}
+
+impl DumperError {
+    /// Creates a [`DumperError::InvalidImage`] error for the bytes `buf` read from `module_name`.
+    pub(super) fn invalid_image(module_name: &str, buf: &[u8], source: pelite::Error) -> Self {
+        Self::InvalidImage {
+            module_name: module_name.to_string(),
+            len: buf.len(),
+            expected: image_size(buf),
+            source,
+        }
+    }
+}
+
+/// Returns the `SizeOfImage` of the PE headers in `buf`, without validating anything else.
+fn image_size(buf: &[u8]) -> Option<usize> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            buf.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    // IMAGE_DOS_HEADER::e_lfanew, then IMAGE_OPTIONAL_HEADER64::SizeOfImage.
+    let nt_headers = read_u32(0x3C)? as usize;
+
+    read_u32(nt_headers + 0x50).map(|size| size as usize)
+}
+
+/// Disassembles the first `count` instructions at the match location of every pattern.
+#[cfg(feature = "disasm")]
+pub fn disasm<P: Process + MemoryView>(process: &mut P, count: usize) -> Result<DisasmMap> {
+    let mut map = BTreeMap::new();
+
+    for (module_name, patterns, _) in modules() {
+        let module = process.module_by_name(module_name)?;
+
+        let buf = read_module(process, &module)?;
+
+        let view = PeView::from_bytes(&buf)?;
+
+        map.insert(
+            module_name.to_string(),
+            disasm_matches(&view, patterns, count),
+        );
+    }
+
+    Ok(map)
+}
+
+#[cfg(feature = "disasm")]
+fn disasm_matches(
+    view: &PeView<'_>,
+    patterns: &Patterns,
+    count: usize,
+) -> BTreeMap<String, Vec<String>> {
+    let mut map = BTreeMap::new();
+
+    for (&name, (pat, _, attrs)) in patterns {
+        let mut save = vec![0; save_len(pat)];
+
+        if attrs.export || !view.scanner().finds_code(pat, &mut save) {
+            continue;
+        }
+
+        let Ok(code) = view.slice(save[0], 1, 1) else {
+            continue;
+        };
+
+        let ip = view.optional_header().ImageBase + save[0] as u64;
+
+        map.insert(
+            name.to_string(),
+            super::disasm::disassemble(code, ip, count),
+        );
+    }
+
+    map
+}
+
+/// Resolves the offsets of `modules` from their files on disk, looking for each module in `dirs`
+/// in order.
+///
+/// Offsets resolved by following code are the same as in a running process, but callbacks that
+/// read data initialized at runtime will see the values stored in the file instead.
+pub fn offsets_from_disk(
+    registry: &Registry,
+    modules: &[&str],
+    dirs: &[PathBuf],
+    strict: bool,
+) -> Result<OffsetMap, DumperError> {
+    warn!("resolving offsets from disk, offsets that depend on runtime data may be unreliable");
+
+    offsets_with_reader(registry, modules, strict, |module_name| {
+        let Some(path) = dirs
+            .iter()
+            .map(|dir| dir.join(module_name))
+            .find(|path| path.is_file())
+        else {
+            return Err(DumperError::ModuleNotFound(module_name.to_string()));
+        };
+
+        let map = FileMap::open(&path)
+            .map_err(|err| DumperError::ReadFailed(format!("{}: {}", path.display(), err)))?;
+
+        let file = PeFile::from_bytes(&map).map_err(|source| DumperError::InvalidImage {
+            module_name: module_name.to_string(),
+            len: map.len(),
+            expected: None,
+            source,
+        })?;
+
+        Ok(file.to_view())
+    })
+}
+
+/// Warns about every pattern with fewer than `min_len` concrete bytes, which are prone to false
+/// matches. Returns the number of patterns that were too short.
+pub fn check_pattern_lengths(min_len: usize) -> usize {
+    modules()
+        .into_iter()
+        .map(|(_, patterns, _)| short_patterns(patterns, min_len).len())
+        .sum()
+}
+
+/// Offsets that every module resolves by design, which aren't reported as duplicates.
+const SHARED_NAMES: [&str; 1] = ["dwCreateInterface"];
+
+/// Warns about offset names defined by more than one module, and returns them together with the
+/// modules that define them. Names only need to be unique within a module, but collisions can
+/// surprise consumers of flat outputs. Offsets inserted by callbacks aren't checked.
+pub fn check_duplicate_names() -> Vec<(&'static str, Vec<&'static str>)> {
+    let modules: Vec<_> = modules()
+        .into_iter()
+        .map(|(module_name, patterns, _)| (module_name, patterns))
+        .collect();
+
+    duplicate_names(&modules)
+}
+
+fn duplicate_names(
+    modules: &[(&'static str, &'static Patterns)],
+) -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut owners: BTreeMap<&'static str, Vec<&'static str>> = BTreeMap::new();
+
+    for &(module_name, patterns) in modules {
+        for &name in patterns.keys() {
+            owners.entry(name).or_default().push(module_name);
+        }
+    }
+
+    owners.retain(|name, modules| modules.len() > 1 && !SHARED_NAMES.contains(name));
+
+    for (name, modules) in &owners {
+        warn!(
+            "offset defined by multiple modules: {} ({})",
+            name,
+            modules.join(", ")
+        );
+    }
+
+    owners.into_iter().collect()
+}
+
+/// Warns about offsets of the same module that share a pattern, which is almost always a
+/// copy-paste mistake, and returns their names by module. Whitespace is ignored when comparing,
+/// since it doesn't change what a pattern matches.
+pub fn check_duplicate_patterns() -> Vec<(&'static str, Vec<&'static str>)> {
+    modules()
+        .into_iter()
+        .flat_map(|(module_name, patterns, _)| {
+            duplicate_patterns(patterns)
+                .into_iter()
+                .map(move |names| (module_name, names))
+        })
+        .collect()
+}
+
+fn duplicate_patterns(patterns: &Patterns) -> Vec<Vec<&'static str>> {
+    let mut names_by_source: BTreeMap<String, Vec<&'static str>> = BTreeMap::new();
+
+    for (&name, (_, _, attrs)) in patterns {
+        if attrs.export {
+            continue;
+        }
+
+        let source: String = attrs.source.split_whitespace().collect();
+
+        names_by_source.entry(source).or_default().push(name);
+    }
+
+    names_by_source
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(source, mut names)| {
+            names.sort();
+
+            warn!(
+                "offsets share the same pattern: {} ({})",
+                names.join(", "),
+                source
+            );
+
+            names
+        })
+        .collect()
+}
+
+fn short_patterns(patterns: &Patterns, min_len: usize) -> Vec<&'static str> {
+    let mut names = Vec::new();
+
+    for (&name, (pat, _, attrs)) in patterns {
+        if attrs.export {
+            continue;
+        }
+
+        let len = concrete_len(pat);
+
+        if len < min_len {
+            warn!(
+                "short pattern: {} ({} concrete bytes, expected at least {})",
+                name, len, min_len
+            );
+
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+/// Returns an error listing every offset that isn't in `offsets`, unless it's marked as
+/// `#[optional]`. Missing optional offsets are only logged. If none are missing, offsets whose
+/// callbacks failed are returned as [`DumperError::CallbackFailed`] instead.
+pub fn check_missing(offsets: &OffsetMap) -> Result<(), DumperError> {
+    let missing: Vec<_> = modules()
+        .into_iter()
+        .flat_map(|(module_name, patterns, _)| {
+            missing_required(patterns, offsets.get(module_name))
+                .into_iter()
+                .map(move |name| format!("{}::{}", module_name, name))
+        })
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(DumperError::NoMatches(missing));
+    }
+
+    let failed_callbacks = failed_callbacks();
+
+    let failed: Vec<_> = modules()
+        .into_iter()
+        .filter_map(|(module_name, ..)| Some((module_name, failed_callbacks.get(module_name)?)))
+        .flat_map(|(module_name, names)| {
+            names
+                .iter()
+                .map(move |name| format!("{}::{}", module_name, name))
+        })
+        .collect();
+
+    if !failed.is_empty() {
+        return Err(DumperError::CallbackFailed(failed));
+    }
+
+    Ok(())
+}
+
+fn missing_required(
+    patterns: &Patterns,
+    offsets: Option<&BTreeMap<String, Rva>>,
+) -> Vec<&'static str> {
+    let mut names = Vec::new();
+
+    for (&name, (_, _, attrs)) in patterns {
+        if offsets.is_some_and(|offsets| offsets.contains_key(name)) {
+            continue;
+        }
+
+        if attrs.optional {
+            warn!("missing optional offset: {}", name);
+        } else {
+            names.push(name);
+        }
+    }
+
+    names.sort();
+
+    names
+}
+
+/// Converts a pattern into the byte array and `x`/`?` mask most runtime scanners take, with the
+/// wildcard bytes set to zero. Jumps to other code can't be expressed that way, so only the bytes up
+/// to the first atom other than bytes, wildcards, reads, saves or a `${...}` jump are converted.
+pub fn mask_bytes(pat: &[Atom]) -> (Vec<u8>, String) {
+    fn wildcards(bytes: &mut Vec<u8>, mask: &mut String, count: u8) {
+        bytes.extend(std::iter::repeat_n(0, count as usize));
+        mask.extend(std::iter::repeat_n('?', count as usize));
+    }
+
+    let mut bytes = Vec::new();
+    let mut mask = String::new();
+
+    // The depth of the `${...}` jump being skipped, if any.
+    let mut depth = 0;
+
+    for atom in pat {
+        match *atom {
+            Atom::Push(_) if depth > 0 => depth += 1,
+            Atom::Pop if depth > 0 => depth -= 1,
+            _ if depth > 0 => {}
+            Atom::Byte(byte) => {
+                bytes.push(byte);
+                mask.push('x');
+            }
+            // The jump target is scanned elsewhere, but the displacement is skipped in place.
+            Atom::Push(skip) => {
+                wildcards(&mut bytes, &mut mask, skip);
+
+                depth = 1;
+            }
+            Atom::Skip(count) => wildcards(&mut bytes, &mut mask, count),
+            Atom::ReadI8(_) | Atom::ReadU8(_) => wildcards(&mut bytes, &mut mask, 1),
+            Atom::ReadI16(_) | Atom::ReadU16(_) => wildcards(&mut bytes, &mut mask, 2),
+            Atom::ReadI32(_) | Atom::ReadU32(_) => wildcards(&mut bytes, &mut mask, 4),
+            Atom::Save(_) | Atom::Nop => {}
+            _ => break,
+        }
+    }
+
+    (bytes, mask)
+}
+
+/// Returns the number of bytes in a compiled pattern that must match exactly.
+fn concrete_len(pat: &[Atom]) -> usize {
+    pat.iter()
+        .filter(|atom| matches!(atom, Atom::Byte(_)))
+        .count()
+}
+
+/// An offset that resolved to different RVAs in the maps passed to [`merge`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub module_name: String,
+    pub name: String,
+    /// The distinct values that were seen, in the order of the maps they first appeared in.
+    pub values: Vec<Rva>,
+}
+
+/// Combines the offsets of several dumps of the same build. Offsets present in any map are kept,
+/// and where the maps disagree the most common value wins, with ties going to the earliest map.
+pub fn merge(maps: &[OffsetMap]) -> (OffsetMap, Vec<Conflict>) {
+    let mut seen: BTreeMap<(&str, &str), Vec<Rva>> = BTreeMap::new();
+
+    for map in maps {
+        for (module_name, offsets) in map {
+            for (name, &value) in offsets {
+                seen.entry((module_name.as_str(), name.as_str()))
+                    .or_default()
+                    .push(value);
+            }
+        }
+    }
+
+    let mut merged = OffsetMap::new();
+    let mut conflicts = Vec::new();
+
+    for ((module_name, name), values) in seen {
+        let mut distinct: Vec<Rva> = Vec::new();
+
+        for &value in &values {
+            if !distinct.contains(&value) {
+                distinct.push(value);
+            }
+        }
+
+        let count = |value: &Rva| values.iter().filter(|&v| v == value).count();
+
+        // `max_by_key` returns the last maximum, so iterate in reverse to prefer earlier maps.
+        let value = *distinct
+            .iter()
+            .rev()
+            .max_by_key(|value| count(*value))
+            .unwrap();
+
+        if distinct.len() > 1 {
+            warn!(
+                "conflicting offset: {} in {} ({:X?})",
+                name, module_name, distinct
+            );
+
+            conflicts.push(Conflict {
+                module_name: module_name.to_string(),
+                name: name.to_string(),
+                values: distinct,
+            });
+        }
+
+        merged
+            .entry(module_name.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+    }
+
+    (merged, conflicts)
+}
+
+/// Reads the build number of the game using the resolved `dwBuildNumber` offset.
+pub fn build_number<P: Process + MemoryView>(process: &mut P, offsets: &OffsetMap) -> Option<u32> {
+    offsets.iter().find_map(|(module_name, offsets)| {
+        let module = process.module_by_name(module_name).ok()?;
+        let offset = offsets.get("dwBuildNumber")?;
+
+        process
+            .read::<u32>(module.base + *offset as umem)
+            .data_part()
+            .ok()
+    })
+}
+
+/// Reads the build number of the game by resolving only the `dwBuildNumber` pattern of
+/// `engine2.dll`, without scanning the other modules.
+pub fn read_build_number<P: Process + MemoryView>(process: &mut P) -> Option<u32> {
+    let module_name = dll_name("engine2")?;
+    let (pat, _, attrs) = engine2::PATTERNS.get("dwBuildNumber")?;
+
+    let builtin = CompiledPattern::Static(*pat);
+    let pat = pattern_override("engine2", "dwBuildNumber").unwrap_or(&builtin);
+
+    let module = process.module_by_name(module_name).ok()?;
+    let buf = read_module(process, &module).ok()?;
+    let view = PeView::from_bytes(&buf).ok()?;
+
+    let (rva, _) = resolve_entry(&view, "dwBuildNumber", pat, attrs)?;
+
+    process
+        .read::<u32>(module.base + rva as umem)
+        .data_part()
+        .ok()
+}
+
+/// Moves an absolute `address` from a module loaded at `old_base` to the same module loaded at
+/// `new_base`. Returns `None` if the address lies below `old_base` or the result overflows.
+pub fn rebase(address: u64, old_base: u64, new_base: u64) -> Option<u64> {
+    address.checked_sub(old_base)?.checked_add(new_base)
+}
+
+/// Returns the base address of every module in `offsets` that's loaded in the process.
+pub fn module_bases<P: Process + MemoryView>(process: &mut P, offsets: &OffsetMap) -> ModuleBases {
+    offsets
+        .keys()
+        .filter_map(|module_name| {
+            let module = process.module_by_name(module_name).ok()?;
+
+            Some((module_name.clone(), module.base.to_umem() as u64))
+        })
+        .collect()
+}
+
+/// Parses a literal byte sequence such as `48 8b 05` or `488b05`. Unlike patterns, wildcards and
+/// other pattern syntax aren't allowed.
+pub fn parse_bytes(s: &str) -> Result<Vec<u8>> {
+    let digits: String = s.split_whitespace().collect();
+
+    if digits.is_empty() {
+        bail!("empty byte sequence");
+    }
+
+    if digits.len() % 2 != 0 {
+        bail!("odd number of hex digits in byte sequence: {}", s);
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16).with_context(|| {
+                format!(
+                    "invalid byte {:?} in byte sequence: {}",
+                    &digits[i..i + 2],
+                    s
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the RVA of every occurrence of `bytes` in the image of a module, including overlapping
+/// ones, such as to find a known sequence near an offset whose pattern no longer matches.
+pub fn find_bytes(image: &[u8], bytes: &[u8]) -> Vec<Rva> {
+    if bytes.is_empty() {
+        return Vec::new();
+    }
+
+    image
+        .windows(bytes.len())
+        .enumerate()
+        .filter(|(_, window)| *window == bytes)
+        .map(|(rva, _)| rva as Rva)
+        .collect()
+}
+
+/// Formats a table of each offset with its RVA and its absolute address at the module bases in
+/// `bases`. Offsets of modules without a known base are listed with `?` as their address, and
+/// constants and fields, which aren't relative to the module base, with `-`.
+pub fn absolute_table(offsets: &OffsetMap, bases: &ModuleBases) -> String {
+    let expressions = expressions(offsets);
+
+    let name_width = offsets
+        .iter()
+        .flat_map(|(module_name, offsets)| {
+            offsets
+                .keys()
+                .map(move |name| module_name.len() + name.len() + 2)
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut out = format!(
+        "{:<name_width$}  {:>10}  {:>18}\n",
+        "offset", "rva", "address"
+    );
+
+    for (module_name, offsets) in offsets {
+        let base = bases.get(module_name).copied();
+
+        for (name, value) in offsets {
+            let address = match base {
+                _ if !is_address(&expressions[module_name][name]) => "-".to_string(),
+                Some(base) => format!("{:#018X}", base + *value as u64),
+                None => "?".to_string(),
+            };
+
+            let _ = writeln!(
+                out,
+                "{:<name_width$}  {:>10}  {:>18}",
+                format!("{}::{}", module_name, name),
+                format!("{:#X}", value),
+                address
+            );
+        }
+    }
+
+    out
+}
+
+/// Returns the attributes of the pattern that resolves `name` in `module_name`, if any. Offsets
+/// inserted by callbacks don't have attributes of their own.
+pub fn pattern_attrs(module_name: &str, name: &str) -> Option<PatternAttrs> {
+    modules()
+        .into_iter()
+        .find(|(module, ..)| *module == module_name)
+        .and_then(|(_, patterns, _)| patterns.get(name))
+        .map(|(_, _, attrs)| *attrs)
+}
+
+/// Applies a callback-computed `delta` to `base`, returning `None` instead of silently wrapping
+/// around if the result can't be represented as an RVA.
+fn checked_offset(name: &str, base: Rva, delta: i64) -> Option<Rva> {
+    let value = base as i64 + delta;
+
+    match Rva::try_from(value) {
+        Ok(rva) => Some(rva),
+        Err(_) => {
+            warn!(
+                "offset out of range: {} ({:#X} + {} = {})",
+                name, base, delta, value
+            );
+
+            None
+        }
+    }
+}
+
+/// Converts a callback-computed `delta` that isn't relative to the module base, such as a field
+/// offset, to the bits of an `i32`. Negative deltas mark the offset as signed, so that it's written
+/// as a negative value instead of wrapping around. Returns `None` if it doesn't fit in an `i32`.
+fn checked_delta(name: &str, delta: i64) -> Option<Rva> {
+    let Ok(delta) = i32::try_from(delta) else {
+        warn!("offset out of range: {} ({})", name, delta);
+
+        return None;
+    };
+
+    if delta < 0 {
+        PENDING_SIGNED.with_borrow_mut(|pending| pending.push(name.to_string()));
+    }
+
+    Some(delta as Rva)
+}
+
+/// Resolves the RVA of a single entry in `pattern_map!` and how far it can be trusted, logging why
+/// if it can't be resolved.
+fn resolve_entry(
+    view: &PeView<'_>,
+    name: &str,
+    pat: &CompiledPattern,
+    attrs: &PatternAttrs,
+) -> Option<(Rva, Confidence)> {
+    if attrs.export {
+        let rva = export_rva(view, attrs.source);
+
+        if rva.is_none() {
+            error!("export not found: {} ({})", name, attrs.source);
+        }
+
+        return rva.map(|rva| (rva, Confidence::High));
+    }
+
+    let pat = pat.atoms();
+    let mut save = vec![0; save_len(pat)];
+
+    let Some(confidence) = find_pattern(view, pat, attrs, &mut save) else {
+        error!("outdated pattern: {}", name);
+
+        if log_enabled!(Level::Debug) {
+            report_near_miss(view, name, pat);
+        }
+
+        return None;
+    };
+
+    if DEBUG_CAPTURES.load(Ordering::Relaxed) {
+        debug!("captures of {}: {}", name, format_captures(&save));
+    }
+
+    if confidence == Confidence::Low && !is_executable(view, save[0]) {
+        warn!("pattern matched outside of code: {} (low confidence)", name);
+    } else if confidence == Confidence::Low {
+        warn!(
+            "ambiguous pattern: {} (low confidence, using the first match)",
+            name
+        );
+    } else {
+        debug!("confidence of {}: {}", name, confidence);
+    }
+
+    // Captures read with `i1`, `i2` or `i4` are already sign-extended to the bits of an `i32`,
+    // which the generated files write as a negative value for signed offsets.
+    Some((save[1], confidence))
+}
+
+/// Returns the RVA of the exported symbol `name`, if any. Forwarded exports aren't followed.
+fn export_rva(view: &PeView<'_>, name: &str) -> Option<Rva> {
+    match view.exports().ok()?.by().ok()?.name(name).ok()? {
+        Export::Symbol(&rva) => Some(rva),
+        Export::Forward(_) => None,
+    }
+}
+
+/// Finds the first match of a pattern in the section named by its attributes, or in the code
+/// section by default, clamped to `attrs.scan_range`. Matches that don't start at a multiple of
+/// `attrs.align` are ignored. The confidence is low if there's more than one match, which is
+/// counted in the same scan.
+fn find_pattern(
+    view: &PeView<'_>,
+    pat: &[Atom],
+    attrs: &PatternAttrs,
+    save: &mut [Rva],
+) -> Option<Confidence> {
+    let mut found = vec![0; save.len()];
+    let mut count = 0;
+
+    for range in scan_ranges(view, attrs)? {
+        let mut matches = view.scanner().matches(pat, range);
+
+        // No need to keep scanning once the pattern is known to be ambiguous.
+        while count < 2 && matches.next(&mut found) {
+            if attrs.align > 1 && found[0] % attrs.align != 0 {
+                continue;
+            }
+
+            if count == 0 {
+                save.copy_from_slice(&found);
+            }
+
+            count += 1;
+        }
+    }
+
+    match count {
+        0 => None,
+        1 if is_executable(view, save[0]) => Some(Confidence::High),
+        _ => Some(Confidence::Low),
+    }
+}
+
+/// Returns whether `rva` lies in executable code. Images without a section containing it only have
+/// the code range of the optional header, which is scanned as code.
+fn is_executable(view: &PeView<'_>, rva: Rva) -> bool {
+    const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+    view.section_headers()
+        .iter()
+        .find(|section| section.virtual_range().contains(&rva))
+        .is_none_or(|section| section.Characteristics & IMAGE_SCN_MEM_EXECUTE != 0)
+}
+
+/// Returns the ranges to scan for a pattern with `attrs`, which are its section if it names one and
+/// otherwise every section that's both executable and readable, limited to its scan range. Images
+/// without such a section fall back to the code range of the optional header.
+fn scan_ranges(view: &PeView<'_>, attrs: &PatternAttrs) -> Option<Vec<Range<Rva>>> {
+    const IMAGE_SCN_MEM_EXECUTE_READ: u32 = 0x6000_0000;
+
+    let mut ranges: Vec<_> = if attrs.section.is_empty() {
+        view.section_headers()
+            .iter()
+            .filter(|section| {
+                section.Characteristics & IMAGE_SCN_MEM_EXECUTE_READ == IMAGE_SCN_MEM_EXECUTE_READ
+            })
+            .map(|section| section.virtual_range())
+            .collect()
+    } else {
+        match view.section_headers().by_name(attrs.section) {
+            Some(section) => vec![section.virtual_range()],
+            None => {
+                warn!("section not found: {}", attrs.section);
+
+                return None;
+            }
+        }
+    };
+
+    if ranges.is_empty() {
+        let header = view.optional_header();
+
+        ranges.push(header.BaseOfCode..header.BaseOfCode + header.SizeOfCode);
+    }
+
+    if attrs.scan_range != (0, 0) {
+        let (start, end) = attrs.scan_range;
+
+        ranges = ranges
+            .into_iter()
+            .map(|range| range.start.max(start)..range.end.min(end))
+            .filter(|range| !range.is_empty())
+            .collect();
+    }
+
+    Some(ranges)
+}
+
+/// The longest partial match of the leading bytes of a pattern that failed to match.
+#[derive(Debug, PartialEq, Eq)]
+struct NearMiss {
+    /// Where the partial match starts.
+    rva: Rva,
+    /// The number of bytes that matched.
+    matched: usize,
+    /// The expected and the found byte at `rva + matched`, or `None` if all leading bytes matched
+    /// and the pattern failed further on.
+    mismatch: Option<(u8, u8)>,
+}
+
+/// Returns the bytes a pattern starts with, up to its first atom that isn't a byte or a skip.
+/// Skipped bytes are `None`.
+fn leading_bytes(pat: &[Atom]) -> Vec<Option<u8>> {
+    let mut bytes = Vec::new();
+
+    for atom in pat {
+        match *atom {
+            Atom::Byte(byte) => bytes.push(Some(byte)),
+            Atom::Skip(count) => bytes.extend((0..count).map(|_| None)),
+            Atom::Save(_) => {}
+            _ => break,
+        }
+    }
+
+    bytes
+}
+
+fn near_miss(view: &PeView<'_>, pat: &[Atom]) -> Option<NearMiss> {
+    let expected = leading_bytes(pat);
+
+    if expected.is_empty() {
+        return None;
+    }
+
+    let header = view.optional_header();
+    let code = view
+        .derva_slice::<u8>(header.BaseOfCode, header.SizeOfCode as usize)
+        .ok()?;
+
+    let mut best: Option<NearMiss> = None;
+
+    for start in 0..code.len() {
+        let window = &code[start..];
+
+        let matched = expected
+            .iter()
+            .zip(window)
+            .take_while(|(expected, found)| expected.is_none_or(|byte| byte == **found))
+            .count();
+
+        if best.as_ref().is_some_and(|best| best.matched >= matched) {
+            continue;
+        }
+
+        let mismatch = match (expected.get(matched), window.get(matched)) {
+            (Some(&Some(expected)), Some(&found)) => Some((expected, found)),
+            _ => None,
+        };
+
+        best = Some(NearMiss {
+            rva: header.BaseOfCode + start as Rva,
+            matched,
+            mismatch,
+        });
+
+        if matched == expected.len() {
+            break;
+        }
+    }
+
+    best.filter(|best| best.matched > 0)
+}
+
+fn report_near_miss(view: &PeView<'_>, name: &str, pat: &[Atom]) {
+    let Some(miss) = near_miss(view, pat) else {
+        return;
+    };
+
+    match miss.mismatch {
+        Some((expected, found)) => debug!(
+            "near miss: {} matched {} bytes at {:#X}, expected {:02X} but found {:02X} at {:#X}",
+            name,
+            miss.matched,
+            miss.rva,
+            expected,
+            found,
+            miss.rva + miss.matched as Rva
+        ),
+        None => debug!(
+            "near miss: {} matched its leading {} bytes at {:#X}",
+            name, miss.matched, miss.rva
+        ),
+    }
+}
+
+fn verify_signature(view: &PeView<'_>, name: &str, rva: Rva, expected: &[u8]) -> bool {
+    let Some(warning) = signature_warning(view, name, rva, expected) else {
+        return true;
+    };
+
+    warn!("{}", warning);
+
+    false
+}
+
+/// Returns the warning logged by [`verify_signature`] if the bytes at `rva` aren't `expected`.
+fn signature_warning(view: &PeView<'_>, name: &str, rva: Rva, expected: &[u8]) -> Option<String> {
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let found = match view.derva_slice::<u8>(rva, expected.len()) {
+        Ok(bytes) if bytes == expected => return None,
+        Ok(bytes) => hex(bytes),
+        Err(_) => "unreadable bytes".to_string(),
+    };
+
+    Some(format!(
+        "signature mismatch: {} at {:#X} (expected {}, found {})",
+        name,
+        rva,
+        hex(expected),
+        found
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+
+    use serde_json::Value;
+
+    use super::*;
+
+    use crate::output::PartialWriter;
+    use crate::source2::InterfaceReg;
+
+    fn setup() -> Result<IntoProcessInstanceArcBox<'static>> {
+        let os = memflow_native::create_os(&OsArgs::default(), LibArc::default())?;
+
+        // Allow running the tests against a differently named build of the game.
+        let process_name = env::var("CS2_PROCESS_NAME").unwrap_or_else(|_| "cs2.exe".to_string());
+
+        let process = os.into_process_by_name(&process_name)?;
+
+        Ok(process)
+    }
+
+    /// Builds a minimal PE image with a single `.text` section at RVA `0x1000` containing `code`.
+    fn build_pe(code: &[u8]) -> Vec<u8> {
+        build_pe_with(&[(b".text", code, 0x60000020)])
+    }
+
+    /// Builds a minimal PE image with `sections` on consecutive pages from RVA `0x1000`, each
+    /// given as its name, contents and characteristics. The first section is the code section.
+    fn build_pe_with(sections: &[(&[u8], &[u8], u32)]) -> Vec<u8> {
+        const FIRST_RVA: u32 = 0x1000;
+
+        let sizes: Vec<u32> = sections
+            .iter()
+            .map(|(_, data, _)| (data.len() as u32).next_multiple_of(0x1000).max(0x1000))
+            .collect();
+
+        let image_size = FIRST_RVA + sizes.iter().sum::<u32>();
+
+        let mut buf = vec![0u8; image_size as usize];
+
+        let put_u16 = |buf: &mut Vec<u8>, off: usize, value: u16| {
+            buf[off..off + 2].copy_from_slice(&value.to_le_bytes())
+        };
+
+        let put_u32 = |buf: &mut Vec<u8>, off: usize, value: u32| {
+            buf[off..off + 4].copy_from_slice(&value.to_le_bytes())
+        };
+
+        // IMAGE_DOS_HEADER
+        put_u16(&mut buf, 0x00, 0x5A4D);
+        put_u32(&mut buf, 0x3C, 0x40);
+
+        // IMAGE_NT_HEADERS64
+        put_u32(&mut buf, 0x40, 0x4550);
+
+        // IMAGE_FILE_HEADER
+        put_u16(&mut buf, 0x44, 0x8664);
+        put_u16(&mut buf, 0x46, sections.len() as u16);
+        put_u16(&mut buf, 0x54, 0xF0);
+        put_u16(&mut buf, 0x56, 0x22);
+
+        // IMAGE_OPTIONAL_HEADER64
+        let opt = 0x58;
+
+        put_u16(&mut buf, opt, 0x20B);
+        put_u32(&mut buf, opt + 4, sizes[0]);
+        put_u32(&mut buf, opt + 20, FIRST_RVA);
+        buf[opt + 24..opt + 32].copy_from_slice(&0x180000000u64.to_le_bytes());
+        put_u32(&mut buf, opt + 32, 0x1000);
+        put_u32(&mut buf, opt + 36, 0x200);
+        put_u16(&mut buf, opt + 48, 6);
+        put_u32(&mut buf, opt + 56, image_size);
+        put_u32(&mut buf, opt + 60, 0x200);
+        put_u16(&mut buf, opt + 68, 2);
+        put_u32(&mut buf, opt + 108, 16);
+
+        let mut rva = FIRST_RVA;
+
+        for (i, ((name, data, characteristics), size)) in sections.iter().zip(&sizes).enumerate() {
+            // IMAGE_SECTION_HEADER
+            let sec = opt + 0xF0 + i * 40;
+
+            buf[sec..sec + name.len()].copy_from_slice(name);
+            put_u32(&mut buf, sec + 8, *size);
+            put_u32(&mut buf, sec + 12, rva);
+            put_u32(&mut buf, sec + 16, *size);
+            put_u32(&mut buf, sec + 20, rva);
+            put_u32(&mut buf, sec + 36, *characteristics);
+
+            buf[rva as usize..rva as usize + data.len()].copy_from_slice(data);
+
+            rva += size;
+        }
+
+        buf
+    }
+
+    /// Builds a PE image like [`build_pe`] with an export directory in a `.rdata` section at RVA
+    /// `0x2000`, exporting `name` at `rva`.
+    fn build_pe_with_export(code: &[u8], name: &str, rva: Rva) -> Vec<u8> {
+        let mut exports = vec![0u8; 0x60];
+
+        let put_u32 = |buf: &mut Vec<u8>, off: usize, value: u32| {
+            buf[off..off + 4].copy_from_slice(&value.to_le_bytes())
+        };
+
+        // IMAGE_EXPORT_DIRECTORY, followed by its tables and names.
+        put_u32(&mut exports, 12, 0x2050); // Name
+        put_u32(&mut exports, 16, 1); // Base
+        put_u32(&mut exports, 20, 1); // NumberOfFunctions
+        put_u32(&mut exports, 24, 1); // NumberOfNames
+        put_u32(&mut exports, 28, 0x2028); // AddressOfFunctions
+        put_u32(&mut exports, 32, 0x202C); // AddressOfNames
+        put_u32(&mut exports, 36, 0x2030); // AddressOfNameOrdinals
+
+        put_u32(&mut exports, 0x28, rva);
+        put_u32(&mut exports, 0x2C, 0x2034);
+
+        exports[0x34..0x34 + name.len()].copy_from_slice(name.as_bytes());
+        exports[0x50..0x50 + 8].copy_from_slice(b"test.dll");
+
+        let mut buf = build_pe_with(&[
+            (b".text", code, 0x60000020),
+            (b".rdata", &exports, 0x40000040),
+        ]);
+
+        // IMAGE_OPTIONAL_HEADER64::DataDirectory[IMAGE_DIRECTORY_ENTRY_EXPORT]
+        put_u32(&mut buf, 0x58 + 112, 0x2000);
+        put_u32(&mut buf, 0x58 + 116, 0x60);
+
+        buf
+    }
+
+    fn get_class_field_value(module_name: &str, class_name: &str, field_name: &str) -> Option<u64> {
+        let content =
+            fs::read_to_string(format!("output/{}.json", module_name.replace(".", "_"))).ok()?;
+
+        let value: Value = serde_json::from_str(&content).ok()?;
+
+        value
+            .get(module_name)?
+            .get("classes")?
+            .get(class_name)?
+            .get("fields")?
+            .get(field_name)?
+            .as_u64()
+    }
+
+    fn get_offset_value(module_name: &str, offset_name: &str) -> Option<u64> {
+        let content = fs::read_to_string("output/offsets.json").ok()?;
+        let value: Value = serde_json::from_str(&content).ok()?;
+
+        let offset = value.get(module_name)?.get(offset_name)?;
+
+        offset.as_u64()
+    }
+
+    #[test]
+    fn build_number() -> Result<()> {
+        let mut process = setup()?;
+
+        let engine_base = process.module_by_name("engine2.dll")?.base;
+
+        let offset = get_offset_value("engine2.dll", "dwBuildNumber").unwrap();
+
+        let build_number: u32 = process.read(engine_base + offset).data_part()?;
+
+        println!("build number: {}", build_number);
+
+        Ok(())
+    }
+
+    #[test]
+    fn view_matrix() -> Result<()> {
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let offset = get_offset_value("client.dll", "dwViewMatrix").unwrap();
+
+        let matrix: [[f32; 4]; 4] = process.read(client_base + offset).data_part()?;
+
+        println!("view matrix: {:?}", matrix);
+
+        assert!(matrix.iter().flatten().all(|value| value.is_finite()));
+
+        // The last row computes the clip space `w`, which is the depth along the view direction,
+        // so its first three elements are the camera's forward vector.
+        let [x, y, z, _] = matrix[3];
+
+        assert!(((x * x + y * y + z * z).sqrt() - 1.0).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn global_vars() -> Result<()> {
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let offset = get_offset_value("client.dll", "dwGlobalVars").unwrap();
+
+        let global_vars: u64 = process.read(client_base + offset).data_part()?;
+
+        let cur_map_name = {
+            let addr = process
+                .read_addr64((global_vars + 0x180).into())
+                .data_part()?;
+
+            process.read_utf8(addr, 128).data_part()?
+        };
+
+        println!("current map name: {}", cur_map_name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn derived_expressions() -> Result<()> {
+        // lea rax, [rip + 0x100]; ret; int3 padding; sub rsp, 0x28; mov ecx, ...
+        let mut code = vec![0x48, 0x8D, 0x05, 0x00, 0x01, 0x00, 0x00, 0xC3];
+
+        code.extend([0xCC; 16]);
+        code.extend([0x48, 0x83, 0xEC, 0x28, 0x8B, 0x0D]);
+
+        // lea rcx, [rip + 0xE2]; mov rax, [rcx + 0x1A0]
+        code.extend([0x48, 0x8D, 0x0D, 0xE2, 0x00, 0x00, 0x00]);
+        code.extend([0x48, 0x8B, 0x81, 0xA0, 0x01, 0x00, 0x00]);
+
+        let map = client::offsets(PeView::from_bytes(&build_pe(&code))?)?;
+
+        assert_eq!(map["dwPrediction"], 0x1107);
+        assert_eq!(map["dwLocalPlayerPawn"], 0x12A7);
+
+        let offsets = OffsetMap::from([("client.dll".to_string(), map)]);
+        let expressions = expressions(&offsets);
+
+        assert_eq!(
+            expressions["client.dll"]["dwPrediction"],
+            "client.dll + 0x1107"
+        );
+        assert_eq!(
+            expressions["client.dll"]["dwLocalPlayerPawn"],
+            "dwPrediction + 0x1A0"
+        );
+
+        let fields = OffsetMap::from([(
+            "matchmaking.dll".to_string(),
+            BTreeMap::from([
+                ("dwGameTypes".to_string(), 0x1A0000),
+                ("dwGameTypes_mapName".to_string(), 0x120),
+            ]),
+        )]);
+
+        assert_eq!(
+            expressions(&fields)["matchmaking.dll"]["dwGameTypes_mapName"],
+            "[dwGameTypes] + 0x120"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_player_controller() -> Result<()> {
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let local_player_controller_offset =
+            get_offset_value("client.dll", "dwLocalPlayerController").unwrap();
+
+        let player_name_offset =
+            get_class_field_value("client.dll", "CBasePlayerController", "m_iszPlayerName")
+                .unwrap();
+
+        let local_player_controller: u64 = process
+            .read(client_base + local_player_controller_offset)
+            .data_part()?;
+
+        let player_name = process
+            .read_utf8((local_player_controller + player_name_offset).into(), 4096)
+            .data_part()?;
+
+        println!("local player name: {}", player_name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_player_pawn() -> Result<()> {
+        #[derive(Debug, Pod)]
+        #[repr(C)]
+        struct Vector3D {
+            x: f32,
+            y: f32,
+            z: f32,
+        }
+
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let local_player_pawn_offset = get_offset_value("client.dll", "dwLocalPlayerPawn").unwrap();
+
+        let game_scene_node_offset =
+            get_class_field_value("client.dll", "C_BaseEntity", "m_pGameSceneNode").unwrap();
+
+        let vec_abs_origin_offset =
+            get_class_field_value("client.dll", "CGameSceneNode", "m_vecAbsOrigin").unwrap();
+
+        let local_player_pawn: u64 = process
+            .read(client_base + local_player_pawn_offset)
+            .data_part()?;
+
+        let game_scene_node: u64 = process
+            .read((local_player_pawn + game_scene_node_offset).into())
+            .data_part()?;
+
+        let vec_abs_origin: Vector3D = process
+            .read((game_scene_node + vec_abs_origin_offset).into())
+            .data_part()?;
 
         println!("local player origin: {:?}", vec_abs_origin);
 
         Ok(())
     }
 
+    pattern_map! {
+        test_patterns => {
+            #[deprecated]
+            "dwOld" => pattern!("488b05${'}") => None,
+            #[optional]
+            "dwNew" => pattern!("488d05${'}") => None,
+            #[optional]
+            "dwExported" => export!("GetTestGlobal") => None,
+        },
+        test_signed => {
+            #[signed]
+            "dwField" => pattern!("488b41i1 c3") => None,
+        },
+        test_overwrite => {
+            "dwFirst" => pattern!("488b05${'}") => None,
+            "dwSecond" => pattern!("488d05${'}") => Some(|_view, map, _rva| {
+                map.insert("dwFirst".to_string(), 0);
+            }),
+        },
+        test_confidence => {
+            "dwAmbiguous" => pattern!("488b05${'}") => None,
+            "dwUnique" => pattern!("488b05${'} 488b05") => None,
+        },
+        test_negative_delta => {
+            "dwBase" => pattern!("488d05${'}") => Some(|_view, map, _rva| {
+                if let Some(value) = checked_delta("dwBase_field", -0x10) {
+                    map.insert("dwBase_field".to_string(), value);
+                }
+            }),
+        },
+        test_failed_callback => {
+            "dwPrimary" => pattern!("488d05${'}") => Some(|_view, _map, _rva| {
+                error!("outdated pattern: dwDerived");
+            }),
+            "dwSucceeded" => pattern!("488b05${'}") => Some(|_view, map, rva| {
+                map.insert("dwSucceeded_derived".to_string(), rva + 8);
+            }),
+        },
+        test_duplicates => {
+            "dwOld" => pattern!("488b0d${'}") => None,
+            "dwCreateInterface" => export!("CreateInterface") => None,
+        },
+        test_duplicate_patterns => {
+            "dwFirst" => pattern!("488b0d${'} 4885c9") => None,
+            "dwSecond" => pattern!("48 8b 0d ${'} 48 85 c9") => None,
+            "dwThird" => pattern!("488b15${'} 4885d2") => None,
+            "dwFirstExport" => export!("CreateInterface") => None,
+            "dwSecondExport" => export!("CreateInterface") => None,
+        },
+    }
+
+    #[test]
+    fn signed_displacement() -> Result<()> {
+        // mov rax, [rcx + 0x10]; ret
+        let buf = build_pe(&[0x48, 0x8B, 0x41, 0x10, 0xC3]);
+        let map = test_signed::offsets(PeView::from_bytes(&buf)?)?;
+
+        assert_eq!(map["dwField"], 0x10);
+
+        // mov rax, [rcx - 0x10]; ret
+        let buf = build_pe(&[0x48, 0x8B, 0x41, 0xF0, 0xC3]);
+        let map = test_signed::offsets(PeView::from_bytes(&buf)?)?;
+
+        // Stored as the bits of the sign-extended displacement, and written as a negative value.
+        assert_eq!(map["dwField"], 0xFFFFFFF0);
+        assert!(is_signed("test_signed", "dwField"));
+        assert_eq!(
+            offset_value("test_signed", "dwField", map["dwField"]),
+            -0x10
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn negative_delta() -> Result<()> {
+        // lea rax, [rip + 0x10]
+        let buf = build_pe(&[0x48, 0x8D, 0x05, 0x10, 0x00, 0x00, 0x00]);
+        let map = test_negative_delta::offsets(PeView::from_bytes(&buf)?)?;
+
+        // Stored as the bits of an `i32` instead of being rejected or wrapping around silently.
+        assert_eq!(map["dwBase_field"], 0xFFFFFFF0);
+
+        assert!(is_signed("test_negative_delta", "dwBase_field"));
+        assert!(!is_signed("test_negative_delta", "dwBase"));
+
+        assert_eq!(
+            offset_value("test_negative_delta", "dwBase_field", map["dwBase_field"]),
+            -0x10
+        );
+        assert_eq!(
+            offset_value("test_negative_delta", "dwBase", map["dwBase"]),
+            0x1017
+        );
+
+        let offsets = OffsetMap::from([("test_negative_delta".to_string(), map)]);
+        let expressions = &expressions(&offsets)["test_negative_delta"];
+
+        assert_eq!(expressions["dwBase_field"], "[dwBase] - 0x10");
+
+        assert_eq!(checked_delta("dwTest", i64::from(i32::MIN) - 1), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn callback_overwrites() -> Result<()> {
+        let before = BTreeMap::from([
+            ("dwFirst".to_string(), 0x1007),
+            ("dwSecond".to_string(), 0x100E),
+        ]);
+
+        let mut after = before.clone();
+
+        after.insert("dwFirst".to_string(), 0);
+        after.insert("dwSecond".to_string(), 0x2000);
+        after.insert("dwThird".to_string(), 0x3000);
+
+        assert_eq!(
+            overwritten_offsets("dwSecond", &before, &after),
+            [("dwFirst".to_string(), 0x1007, 0)]
+        );
+        assert!(overwritten_offsets("dwSecond", &before, &before).is_empty());
+
+        // mov rax, [rip]; lea rax, [rip]
+        let buf = build_pe(&[
+            0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let map = test_overwrite::offsets(PeView::from_bytes(&buf)?)?;
+
+        // The scanned value is kept, since callbacks only run once every pattern is scanned.
+        assert_eq!(map["dwFirst"], 0x1007);
+        assert_eq!(map["dwSecond"], 0x100E);
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_offset_names() {
+        assert!(check_duplicate_names().is_empty());
+
+        let duplicates = duplicate_names(&[
+            ("test.dll", &test_patterns::PATTERNS),
+            ("duplicates.dll", &test_duplicates::PATTERNS),
+            ("client.dll", &client::PATTERNS),
+        ]);
+
+        assert_eq!(duplicates, [("dwOld", vec!["test.dll", "duplicates.dll"])]);
+    }
+
+    #[test]
+    fn literal_bytes() -> Result<()> {
+        let bytes = parse_bytes("48 8b05")?;
+
+        assert_eq!(bytes, [0x48, 0x8B, 0x05]);
+
+        let mut image = vec![0xCC; 0x40];
+
+        image[0x10..0x13].copy_from_slice(&bytes);
+        image[0x2D..0x30].copy_from_slice(&bytes);
+
+        assert_eq!(find_bytes(&image, &bytes), [0x10, 0x2D]);
+        assert_eq!(find_bytes(&[0xAA; 4], &[0xAA, 0xAA]), [0, 1, 2]);
+        assert!(find_bytes(&image, &[0x90]).is_empty());
+
+        assert!(parse_bytes("").is_err());
+        assert!(parse_bytes("488").is_err());
+        assert!(parse_bytes("48 ?? 05").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_patterns_in_module() {
+        assert!(check_duplicate_patterns().is_empty());
+        assert!(duplicate_patterns(&test_patterns::PATTERNS).is_empty());
+
+        // Exports of the same symbol aren't patterns, so they aren't reported.
+        assert_eq!(
+            duplicate_patterns(&test_duplicate_patterns::PATTERNS),
+            [vec!["dwFirst", "dwSecond"]]
+        );
+    }
+
+    #[test]
+    fn pattern_sources() {
+        let patterns = patterns();
+
+        assert_eq!(patterns["client.dll"]["dwEntityList"], "488935${'} 4885f6");
+        assert_eq!(test_patterns::PATTERNS["dwOld"].2.source, "488b05${'}");
+    }
+
+    #[test]
+    fn pattern_override_sources() -> Result<()> {
+        let entry = parse_pattern_override("client:dwEntityList=48 89 35 ${'} 48 85 f6")?;
+
+        assert_eq!(
+            entry.pattern.atoms(),
+            pattern::parse("48 89 35 ${'} 48 85 f6").unwrap()
+        );
+
+        let mut patterns = patterns();
+
+        apply_overrides(&mut patterns, &[entry]);
+
+        assert_eq!(
+            patterns["client.dll"]["dwEntityList"],
+            "48 89 35 ${'} 48 85 f6"
+        );
+        assert_eq!(
+            patterns["client.dll"]["dwGameRules"],
+            client::PATTERNS["dwGameRules"].2.source
+        );
+
+        assert!(matches!(
+            parse_pattern_override("client:dwEntityList"),
+            Err(DumperError::InvalidConfig(_))
+        ));
+        assert!(parse_pattern_override("server:dwEntityList=488935").is_err());
+        assert!(parse_pattern_override("client:dwMissing=488935").is_err());
+        assert!(parse_pattern_override("client:dwCreateInterface=488935").is_err());
+        assert!(matches!(
+            parse_pattern_override("client:dwEntityList=zz"),
+            Err(DumperError::InvalidPattern(message)) if message.starts_with("client:dwEntityList")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn offset_catalog() {
+        let catalog = catalog();
+
+        let (_, _, pat) = catalog
+            .iter()
+            .find(|(module_name, name, _)| *module_name == "client.dll" && *name == "dwEntityList")
+            .unwrap();
+
+        assert!(!pat.is_empty());
+        assert!(catalog.is_sorted_by_key(|&(module_name, name, _)| (module_name, name)));
+        assert!(
+            catalog
+                .iter()
+                .all(|(module_name, ..)| module_names().contains(module_name))
+        );
+    }
+
+    #[test]
+    fn dll_name_override() {
+        let names = DllNames::from([("engine2".to_string(), "engine2_new.dll".to_string())]);
+
+        assert!(validate_dll_names(&names).is_ok());
+
+        let modules = modules_with(&names);
+
+        let (_, patterns, _) = modules
+            .iter()
+            .find(|(dll_name, ..)| *dll_name == "engine2_new.dll")
+            .unwrap();
+
+        assert!(patterns.contains_key("dwBuildNumber"));
+        assert!(
+            !modules
+                .iter()
+                .any(|(dll_name, ..)| *dll_name == "engine2.dll")
+        );
+        assert!(
+            modules
+                .iter()
+                .any(|(dll_name, ..)| *dll_name == "client.dll")
+        );
+
+        let unknown = DllNames::from([("engine3".to_string(), "engine3.dll".to_string())]);
+        let duplicate = DllNames::from([("engine2".to_string(), "client.dll".to_string())]);
+
+        assert!(matches!(
+            validate_dll_names(&unknown),
+            Err(DumperError::InvalidConfig(message)) if message.contains("engine3")
+        ));
+        assert!(validate_dll_names(&duplicate).is_err());
+    }
+
+    #[test]
+    fn dll_names_errors() -> Result<()> {
+        let path =
+            env::temp_dir().join(format!("cs2-dumper-dll-names-{}.json", std::process::id()));
+
+        fs::write(&path, "[\"engine2.dll\"]")?;
+
+        let invalid = load_dll_names(&path);
+
+        fs::remove_file(&path)?;
+
+        assert!(matches!(invalid, Err(DumperError::InvalidConfig(_))));
+        assert!(matches!(
+            load_dll_names(&path),
+            Err(DumperError::ReadFailed(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn deprecated_attribute() {
+        assert!(test_patterns::PATTERNS.get("dwOld").unwrap().2.deprecated);
+        assert!(!test_patterns::PATTERNS.get("dwNew").unwrap().2.deprecated);
+
+        assert!(pattern_attrs("client.dll", "dwEntityList").is_some_and(|attrs| !attrs.deprecated));
+        assert!(pattern_attrs("client.dll", "dwLocalPlayerPawn").is_none());
+    }
+
+    #[test]
+    fn min_pattern_len() {
+        assert_eq!(concrete_len(pattern!("488b05${'}")), 3);
+        assert_eq!(concrete_len(pattern!("488b?? 05")), 3);
+
+        assert!(short_patterns(&test_patterns::PATTERNS, 3).is_empty());
+
+        let mut names = short_patterns(&test_patterns::PATTERNS, 4);
+        names.sort();
+
+        assert_eq!(names, ["dwNew", "dwOld"]);
+    }
+
+    #[test]
+    fn missing_offsets() {
+        let found = |names: &[&str]| -> BTreeMap<String, Rva> {
+            names
+                .iter()
+                .map(|name| (name.to_string(), 0x1000))
+                .collect()
+        };
+
+        // Only optional offsets are missing.
+        assert!(missing_required(&test_patterns::PATTERNS, Some(&found(&["dwOld"]))).is_empty());
+
+        assert_eq!(
+            missing_required(&test_patterns::PATTERNS, Some(&found(&["dwNew"]))),
+            ["dwOld"]
+        );
+
+        assert_eq!(missing_required(&test_patterns::PATTERNS, None), ["dwOld"]);
+
+        assert!(matches!(
+            check_missing(&OffsetMap::new()),
+            Err(DumperError::NoMatches(names)) if names.contains(&"client.dll::dwEntityList".to_string())
+        ));
+    }
+
+    #[test]
+    fn failed_callback() -> Result<()> {
+        // lea rax, [rip + 0x10]; mov rax, [rip + 0x20]
+        let buf = build_pe(&[
+            0x48, 0x8D, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8B, 0x05, 0x20, 0x00, 0x00, 0x00,
+        ]);
+
+        let map = test_failed_callback::offsets(PeView::from_bytes(&buf)?)?;
+
+        // The primary offset is kept even though its callback failed.
+        assert_eq!(map["dwPrimary"], 0x1017);
+        assert_eq!(map["dwSucceeded"], 0x102E);
+        assert_eq!(map["dwSucceeded_derived"], 0x1036);
+        assert!(!map.contains_key("dwDerived"));
+
+        let failed = failed_callbacks();
+
+        assert!(failed["test_failed_callback"].contains("dwPrimary"));
+        assert!(!failed["test_failed_callback"].contains("dwSucceeded"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn feature_filter() {
+        let offsets = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1234),
+                    ("dwViewAngles".to_string(), 0x2345),
+                    ("dwViewMatrix".to_string(), 0x5678),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([
+                    ("dwBuildNumber".to_string(), 0x9ABC),
+                    ("dwWindowWidth".to_string(), 0xDEF0),
+                ]),
+            ),
+            (
+                "matchmaking.dll".to_string(),
+                BTreeMap::from([("dwGameTypes".to_string(), 0x1000)]),
+            ),
+        ]);
+
+        let filtered = filter_by_feature(&offsets, "rendering");
+
+        assert_eq!(
+            filtered,
+            OffsetMap::from([
+                (
+                    "client.dll".to_string(),
+                    BTreeMap::from([("dwViewMatrix".to_string(), 0x5678)]),
+                ),
+                (
+                    "engine2.dll".to_string(),
+                    BTreeMap::from([("dwWindowWidth".to_string(), 0xDEF0)]),
+                ),
+            ])
+        );
+
+        assert!(filter_by_feature(&offsets, "unknown").is_empty());
+    }
+
+    #[test]
+    fn excluded_offsets() -> Result<()> {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwEntityList".to_string(), 0x1234),
+                ("dwSensitivity".to_string(), 0x2345),
+                ("dwSensitivity_sensitivity".to_string(), 0x40),
+                ("dwViewMatrix".to_string(), 0x5678),
+            ]),
+        )]);
+
+        let globs = ["dwSensitivity*".to_string(), "dwView?atrix".to_string()];
+
+        validate_globs(&globs)?;
+
+        assert_eq!(
+            exclude_offsets(&offsets, &globs),
+            OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )])
+        );
+
+        assert_eq!(exclude_offsets(&offsets, &[]), offsets);
+
+        assert!(glob_matches("*List", "dwEntityList"));
+        assert!(glob_matches("dw*y*", "dwEntityList_chunkSize"));
+        assert!(!glob_matches("dwEntity", "dwEntityList"));
+        assert!(!glob_matches("dw?", "dw"));
+
+        assert!(validate_globs(&["".to_string()]).is_err());
+        assert!(validate_globs(&["dw[A-Z]*".to_string()]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transforms() -> Result<()> {
+        let transforms = parse_transforms(&[
+            "client.dll:dwEntityList=div:8".to_string(),
+            "client.dll:dwViewMatrix=identity".to_string(),
+            "engine2.dll:dwBuildNumber=and:0xFFF0".to_string(),
+        ])?;
+
+        assert_eq!(transforms["client.dll"]["dwEntityList"], Transform::Div(8));
+        assert_eq!(
+            transforms["engine2.dll"]["dwBuildNumber"].to_string(),
+            "and:0xFFF0"
+        );
+
+        let offsets = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1238),
+                    ("dwGameRules".to_string(), 0x2000),
+                    ("dwViewMatrix".to_string(), 0x5678),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([("dwBuildNumber".to_string(), 0x1234)]),
+            ),
+        ]);
+
+        let transformed = apply_transforms(&offsets, &transforms);
+
+        assert_eq!(transformed["client.dll"]["dwEntityList"], 0x247);
+        assert_eq!(transformed["client.dll"]["dwGameRules"], 0x2000);
+        assert_eq!(transformed["client.dll"]["dwViewMatrix"], 0x5678);
+        assert_eq!(transformed["engine2.dll"]["dwBuildNumber"], 0x1230);
+
+        for arg in [
+            "dwEntityList=div:8",
+            "client.dll:dwEntityList=div:0",
+            "client.dll:dwEntityList=mul:8",
+            "client.dll:dwEntityList=and:0xZZ",
+        ] {
+            assert!(parse_transforms(&[arg.to_string()]).is_err(), "{}", arg);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn offsets_present() -> Result<()> {
+        let offsets = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([("dwBuildNumber".to_string(), 0x5678)]),
+            ),
+        ]);
+
+        assert_offsets_present(
+            &offsets,
+            &["client.dll:dwEntityList", "engine2.dll:dwBuildNumber"],
+        )?;
+
+        let err = assert_offsets_present(
+            &offsets,
+            &[
+                "client.dll:dwEntityList",
+                "client.dll:dwViewMatrix",
+                "inputsystem.dll:dwInputSystem",
+            ],
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "2 of 3 offsets are missing: client.dll:dwViewMatrix, inputsystem.dll:dwInputSystem"
+        );
+
+        assert!(assert_offsets_present(&offsets, &["dwEntityList"]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cvar_offset() {
+        let interfaces = InterfaceMap::from([(
+            "tier0.dll".to_string(),
+            BTreeMap::from([
+                ("TestScriptMgr001".to_string(), 0x1000),
+                ("VEngineCvar007".to_string(), 0x2000),
+            ]),
+        )]);
+
+        let mut offsets = OffsetMap::new();
+
+        add_cvar_offset(&mut offsets, &InterfaceMap::new());
+
+        assert!(offsets.is_empty());
+
+        add_cvar_offset(&mut offsets, &interfaces);
+
+        assert_eq!(offsets["tier0.dll"]["dwCVar"], 0x2000);
+    }
+
+    #[test]
+    fn entity_list_constants() {
+        let mut offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwEntityList".to_string(), 0x1000)]),
+        )]);
+
+        add_constants(&mut offsets);
+
+        assert_eq!(offsets["client.dll"]["dwEntityList_chunkSize"], 512);
+        assert_eq!(
+            expressions(&offsets)["client.dll"]["dwEntityList_chunkSize"],
+            "0x200"
+        );
+
+        let mut missing = OffsetMap::new();
+
+        add_constants(&mut missing);
+
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn highest_entity_index() -> Result<()> {
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let system_offset = get_offset_value("client.dll", "dwGameEntitySystem").unwrap();
+        let index_offset =
+            get_offset_value("client.dll", "dwGameEntitySystem_highestEntityIndex").unwrap();
+        let chunk_size = get_offset_value("client.dll", "dwEntityList_chunkSize").unwrap();
+
+        let entity_system = process
+            .read_addr64(client_base + system_offset)
+            .data_part()?;
+        let highest_index: i32 = process.read(entity_system + index_offset).data_part()?;
+
+        println!("highest entity index: {}", highest_index);
+
+        // The entity list has 64 chunks, and the world entity always exists.
+        assert!((0..(64 * chunk_size as i32)).contains(&highest_index));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cvar_system() -> Result<()> {
+        let mut process = setup()?;
+
+        let tier0_base = process.module_by_name("tier0.dll")?.base;
+
+        let offset = get_offset_value("tier0.dll", "dwCVar").unwrap();
+
+        let vtable = process.read_addr64(tier0_base + offset).data_part()?;
+
+        assert!(!vtable.is_null());
+
+        println!("cvar system vtable: {:#X}", vtable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_conflict() {
+        let map = |entity_list| {
+            OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), entity_list),
+                    ("dwViewMatrix".to_string(), 0x5678),
+                ]),
+            )])
+        };
+
+        let (merged, conflicts) = merge(&[map(0x1234), map(0x4321), map(0x4321)]);
+
+        assert_eq!(merged, map(0x4321));
+        assert_eq!(
+            conflicts,
+            [Conflict {
+                module_name: "client.dll".to_string(),
+                name: "dwEntityList".to_string(),
+                values: vec![0x1234, 0x4321],
+            }]
+        );
+    }
+
+    #[test]
+    fn negative_offset_delta() {
+        assert_eq!(checked_offset("dwTest", 0x100, 0x80), Some(0x180));
+        assert_eq!(checked_offset("dwTest", 0x100, -0x80), Some(0x80));
+        assert_eq!(checked_offset("dwTest", 0x100, -0x200), None);
+        assert_eq!(checked_offset("dwTest", Rva::MAX, 1), None);
+    }
+
+    #[test]
+    fn in_memory_reader() -> Result<()> {
+        // mov [rip+0x10], rax; lea rax, ...
+        let buf = build_pe(&[0x48, 0x89, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05]);
+
+        let map = offsets_with_reader(
+            &Registry::default(),
+            &["inputsystem.dll"],
+            true,
+            |module_name| {
+                assert_eq!(module_name, "inputsystem.dll");
+
+                Ok(buf.clone())
+            },
+        )?;
+
+        assert_eq!(map["inputsystem.dll"]["dwInputSystem"], 0x1017);
+
+        assert!(matches!(
+            offsets_with_reader(&Registry::default(), &["unknown.dll"], false, |_| Ok(buf.clone())),
+            Err(DumperError::UnknownModule(name)) if name == "unknown.dll"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn partial_output_on_failure() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x89, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05]);
+
+        let out_dir = env::temp_dir().join("cs2-dumper-partial-test");
+        let partial = PartialWriter::new(&out_dir)?;
+
+        let result = offsets_with_sink(
+            &Registry::default(),
+            &["inputsystem.dll", "soundsystem.dll"],
+            true,
+            |module_name| match module_name {
+                "inputsystem.dll" => Ok(buf.clone()),
+                _ => Err(DumperError::ReadFailed(module_name.to_string())),
+            },
+            |module_name, offsets| partial.write_module(module_name, offsets).unwrap(),
+        );
+
+        assert!(result.is_err());
+
+        let content = fs::read_to_string(out_dir.join(PartialWriter::FILE_NAME))?;
+        let lines: Vec<Value> = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["module"], "inputsystem.dll");
+        assert_eq!(lines[0]["offsets"]["dwInputSystem"], 0x1017);
+
+        partial.finish()?;
+
+        assert!(!out_dir.join(PartialWriter::FILE_NAME).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn module_error_policy() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x89, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05]);
+
+        let modules = ["inputsystem.dll", "soundsystem.dll"];
+
+        let reader = |module_name: &str| match module_name {
+            "inputsystem.dll" => Ok(buf.clone()),
+            _ => Err(DumperError::ReadFailed(module_name.to_string())),
+        };
+
+        let map = offsets_with_reader(&Registry::default(), &modules, false, reader)?;
+
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["inputsystem.dll"]);
+        assert!(matches!(
+            offsets_with_reader(&Registry::default(), &modules, true, reader),
+            Err(DumperError::ReadFailed(name)) if name == "soundsystem.dll"
+        ));
+
+        assert!(matches!(
+            offsets_with_reader(&Registry::default(), &modules[..1], true, |_| Ok(vec![
+                0;
+                0x10
+            ])),
+            Err(DumperError::InvalidImage { len: 0x10, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn registered_module() -> Result<()> {
+        fn resolve(view: PeView<'_>) -> (BTreeMap<String, Rva>, BTreeMap<String, String>) {
+            let header = view.optional_header();
+
+            (
+                BTreeMap::from([("dwCustom".to_string(), header.BaseOfCode + 0x10)]),
+                BTreeMap::from([("dwCustom".to_string(), "CCustom::Get".to_string())]),
+            )
+        }
+
+        let mut registry = Registry::default();
+
+        registry.register_module("custom.dll", resolve)?;
+
+        assert!(matches!(
+            registry.register_module("custom.dll", resolve),
+            Err(DumperError::DuplicateModule(name)) if name == "custom.dll"
+        ));
+        assert!(registry.register_module("client.dll", resolve).is_err());
+
+        assert!(registry.module_names().contains(&"custom.dll"));
+        assert!(!module_names().contains(&"custom.dll"));
+
+        let buf = build_pe(&[0xCC; 0x20]);
+        let map = offsets_with_reader(&registry, &["custom.dll"], true, |_| Ok(buf.clone()))?;
+
+        assert_eq!(map["custom.dll"]["dwCustom"], 0x1010);
+        assert_eq!(
+            target("custom.dll", "dwCustom").as_deref(),
+            Some("CCustom::Get")
+        );
+
+        // Other scans don't include the module.
+        assert!(matches!(
+            offsets_with_reader(&Registry::default(), &["custom.dll"], true, |_| {
+                Ok(buf.clone())
+            }),
+            Err(DumperError::UnknownModule(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn module_list() -> Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("cs2-dumper-modules-{}.txt", std::process::id()));
+
+        fs::write(
+            &path,
+            "# Scanned after the built-in modules.\n\nnetworksystem.dll\nvstdlib.dll:dwFoo=488b05${'}\n",
+        )?;
+
+        let mut registry = Registry::default();
+
+        registry.load_module_list(&path)?;
+
+        // Loading the same list again would scan its modules twice.
+        assert!(matches!(
+            registry.load_module_list(&path),
+            Err(DumperError::DuplicateModule(name)) if name == "networksystem.dll"
+        ));
+
+        fs::remove_file(&path)?;
+
+        assert!(matches!(
+            registry.load_module_list(&path),
+            Err(DumperError::ReadFailed(_))
+        ));
+
+        assert!(parse_module_list("client.dll").is_ok());
+        assert!(matches!(
+            parse_module_list("vstdlib.dll:dwFoo"),
+            Err(DumperError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            parse_module_list("vstdlib.dll:dwFoo=zz"),
+            Err(DumperError::InvalidPattern(_))
+        ));
+        assert!(parse_module_list("vstdlib.dll:dwFoo=48\nvstdlib.dll:dwFoo=48").is_err());
+
+        let names = registry.module_names();
+
+        assert!(names.contains(&"networksystem.dll"));
+        assert!(names.contains(&"vstdlib.dll"));
+        assert!(!module_names().contains(&"vstdlib.dll"));
+
+        assert_eq!(registry.patterns()["vstdlib.dll"]["dwFoo"], "488b05${'}");
+        assert!(!patterns().contains_key("vstdlib.dll"));
+
+        // `mov rax, [rip + 0x10]`, so `dwFoo` is the end of the instruction plus 0x10.
+        let mut code = vec![0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00];
+        code.resize(0x20, 0xCC);
+
+        let buf = build_pe(&code);
+        let mut attempted = Vec::new();
+
+        let map = offsets_with_reader(
+            &registry,
+            &["networksystem.dll", "vstdlib.dll"],
+            false,
+            |module_name| {
+                attempted.push(module_name.to_string());
+
+                Ok(buf.clone())
+            },
+        )?;
+
+        assert_eq!(attempted, ["networksystem.dll", "vstdlib.dll"]);
+
+        assert!(map["networksystem.dll"].is_empty());
+        assert_eq!(map["vstdlib.dll"]["dwFoo"], 0x1017);
+
+        let err = registry
+            .check_listed_modules(|dll_name| dll_name != "vstdlib.dll")
+            .unwrap_err();
+
+        assert!(matches!(err, DumperError::ModuleNotFound(ref names) if names == "vstdlib.dll"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cross_module_callback() -> Result<()> {
+        fn resolve_base(view: PeView<'_>) -> (BTreeMap<String, Rva>, BTreeMap<String, String>) {
+            (
+                BTreeMap::from([("dwBase".to_string(), view.optional_header().BaseOfCode)]),
+                BTreeMap::new(),
+            )
+        }
+
+        fn resolve_empty(_view: PeView<'_>) -> (BTreeMap<String, Rva>, BTreeMap<String, String>) {
+            (BTreeMap::new(), BTreeMap::new())
+        }
+
+        let mut registry = Registry::default();
+
+        registry.register_module("cross_user.dll", resolve_empty)?;
+        registry.register_module("cross_provider.dll", resolve_base)?;
+
+        registry.register_cross_module(
+            "cross_user.dll",
+            &["cross_provider.dll"],
+            |_view, map, resolved| {
+                if let Some(&base) = resolved
+                    .get("cross_provider.dll")
+                    .and_then(|offsets| offsets.get("dwBase"))
+                {
+                    map.insert("dwDerived".to_string(), base + 0x10);
+                }
+            },
+        );
+
+        let buf = build_pe(&[0xCC; 0x20]);
+
+        let mut order = Vec::new();
+
+        let map = offsets_with_sink(
+            &registry,
+            &["cross_user.dll", "cross_provider.dll"],
+            true,
+            |_| Ok(buf.clone()),
+            |module_name, _| order.push(module_name.to_string()),
+        )?;
+
+        assert_eq!(order, ["cross_provider.dll", "cross_user.dll"]);
+        assert_eq!(map["cross_user.dll"]["dwDerived"], 0x1010);
+
+        registry.register_cross_module("cross_a.dll", &["cross_b.dll"], |_, _, _| {});
+        registry.register_cross_module("cross_b.dll", &["cross_a.dll"], |_, _, _| {});
+
+        assert!(matches!(
+            registry.resolution_order(&["cross_a.dll", "cross_b.dll", "cross_user.dll"]),
+            Err(DumperError::DependencyCycle(names)) if names == ["cross_a.dll", "cross_b.dll"]
+        ));
+        assert_eq!(
+            registry.resolution_order(&["cross_user.dll", "client.dll"])?,
+            ["cross_user.dll", "client.dll"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_image() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x89, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05]);
+
+        let modules = ["inputsystem.dll", "matchmaking.dll", "soundsystem.dll"];
+
+        let reader = |module_name: &str| -> Result<Vec<u8>, DumperError> {
+            match module_name {
+                // Cut off in the middle of the section headers.
+                "inputsystem.dll" => Ok(buf[..0x100].to_vec()),
+                "matchmaking.dll" => Ok(vec![0xCC; 0x100]),
+                _ => Ok(buf.clone()),
+            }
+        };
+
+        let map = offsets_with_reader(&Registry::default(), &modules, false, reader)?;
+
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["soundsystem.dll"]);
+
+        let err =
+            offsets_with_reader(&Registry::default(), &modules[..1], true, reader).unwrap_err();
+
+        assert!(matches!(
+            err,
+            DumperError::InvalidImage {
+                len: 0x100,
+                expected: Some(0x2000),
+                ..
+            }
+        ));
+
+        assert!(err.to_string().contains("(256 bytes read, 8192 expected)"));
+
+        assert!(matches!(
+            offsets_with_reader(&Registry::default(), &modules[1..2], true, reader),
+            Err(DumperError::InvalidImage { expected: None, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_disk() -> Result<()> {
+        let dir = env::temp_dir().join(format!("cs2-dumper-{}", std::process::id()));
+
+        fs::create_dir_all(&dir)?;
+
+        // The section is stored at its RVA, so the file layout matches the image layout.
+        let buf = build_pe(&[0x48, 0x89, 0x05, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8D, 0x05]);
+
+        fs::write(dir.join("inputsystem.dll"), &buf)?;
+
+        let dirs = [dir.join("missing"), dir.clone()];
+        let result = offsets_from_disk(&Registry::default(), &["inputsystem.dll"], &dirs, true);
+
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(result?["inputsystem.dll"]["dwInputSystem"], 0x1017);
+
+        Ok(())
+    }
+
+    #[test]
+    fn view_angles_callback() -> Result<()> {
+        let callback = client::PATTERNS["dwCSGOInput"].1.unwrap();
+
+        let view_angles = |disp: u32| -> Result<Option<Rva>> {
+            // movsd xmm0, [r8+rsi+disp32]
+            let mut code = vec![0xF2, 0x41, 0x0F, 0x10, 0x84, 0x30];
+            code.extend_from_slice(&disp.to_le_bytes());
+
+            let buf = build_pe(&code);
+            let view = PeView::from_bytes(&buf)?;
+
+            let mut map = BTreeMap::new();
+
+            callback(&view, &mut map, 0x2000);
+
+            Ok(map.get("dwViewAngles").copied())
+        };
+
+        assert_eq!(view_angles(0x5A0)?, Some(0x25A0));
+        assert_eq!(view_angles(0x7FFF0000)?, None);
+
+        let buf = build_pe(&[0xF2, 0x41, 0x0F, 0x10, 0x05]);
+        let view = PeView::from_bytes(&buf)?;
+
+        let mut map = BTreeMap::new();
+
+        callback(&view, &mut map, 0x2000);
+
+        assert!(map.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_alignment() -> Result<()> {
+        let pat = pattern!("488b05${'}");
+
+        let find = |code: &[u8], align| -> Result<Option<Rva>> {
+            let buf = build_pe(code);
+            let view = PeView::from_bytes(&buf)?;
+
+            let mut save = vec![0; save_len(pat)];
+
+            let attrs = PatternAttrs {
+                align,
+                ..PatternAttrs::DEFAULT
+            };
+
+            Ok(find_pattern(&view, pat, &attrs, &mut save).map(|_| save[0]))
+        };
+
+        let mut code = vec![0xCC; 0x20];
+
+        code[0x01..0x08].copy_from_slice(&[0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(find(&code, 0)?, Some(0x1001));
+        assert_eq!(find(&code, 16)?, None);
+
+        code[0x10..0x17].copy_from_slice(&[0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(find(&code, 16)?, Some(0x1010));
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_player_pawn_displacement_from_code() -> Result<()> {
+        let mut code = vec![0xCC; 0x10];
+
+        // lea rdx, [rip + 0x100]; mov rax, [rdx + 0x278]
+        code.extend([0x48, 0x8D, 0x15, 0x00, 0x01, 0x00, 0x00]);
+        code.extend([0x48, 0x8B, 0x82, 0x78, 0x02, 0x00, 0x00]);
+
+        let buf = build_pe(&code);
+        let view = PeView::from_bytes(&buf)?;
+
+        assert_eq!(local_player_pawn_displacement(&view, 0x1117), Some(0x278));
+
+        // The `lea` has to reference the prediction object.
+        assert_eq!(local_player_pawn_displacement(&view, 0x1200), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn local_player_pawn_displacement_rejects_other_loads() -> Result<()> {
+        // lea rcx, [rip + 0x100]; mov rax, [rip + 0x278]
+        let mut code = vec![0xCC; 0x10];
+
+        code.extend([0x48, 0x8D, 0x0D, 0x00, 0x01, 0x00, 0x00]);
+        code.extend([0x48, 0x8B, 0x05, 0x78, 0x02, 0x00, 0x00]);
+
+        let buf = build_pe(&code);
+
+        assert_eq!(
+            local_player_pawn_displacement(&PeView::from_bytes(&buf)?, 0x1117),
+            None
+        );
+
+        // lea rcx, [rip + 0x100]; mov rax, [rdx + 0x278]
+        code.truncate(0x17);
+        code.extend([0x48, 0x8B, 0x82, 0x78, 0x02, 0x00, 0x00]);
+
+        let buf = build_pe(&code);
+
+        assert_eq!(
+            local_player_pawn_displacement(&PeView::from_bytes(&buf)?, 0x1117),
+            None
+        );
+
+        // lea rcx, [rip + 0x100]; mov rax, [rcx + rdx + 0x278]
+        code.truncate(0x17);
+        code.extend([0x48, 0x8B, 0x84, 0x11, 0x78, 0x02, 0x00, 0x00]);
+
+        let buf = build_pe(&code);
+
+        assert_eq!(
+            local_player_pawn_displacement(&PeView::from_bytes(&buf)?, 0x1117),
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn debug_captures() -> Result<()> {
+        // mov rax, [rcx + 0x10]; lea rax, [rip]
+        let buf = build_pe(&[
+            0x48, 0x8B, 0x41, 0x10, 0x48, 0x8D, 0x05, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let view = PeView::from_bytes(&buf)?;
+
+        let pat = pattern!("488b41u1 488d05${'}");
+        let mut save = vec![0; save_len(pat)];
+
+        assert!(find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save).is_some());
+        assert_eq!(format_captures(&save), "[0] 0x1000, [1] 0x10, [2] 0x100B");
+
+        Ok(())
+    }
+
+    #[test]
+    fn compiled_and_parsed_patterns() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x8B, 0x05, 0x10, 0x00, 0x00, 0x00]);
+        let view = PeView::from_bytes(&buf)?;
+
+        let compiled = CompiledPattern::Static(pattern!("488b05${'}"));
+        let parsed = CompiledPattern::parse("48 8b 05 ${'}").unwrap();
+
+        assert_eq!(compiled.atoms(), parsed.atoms());
+
+        for pat in [&compiled, &parsed] {
+            assert_eq!(
+                resolve_entry(&view, "dwTest", pat, &PatternAttrs::DEFAULT),
+                Some((0x1017, Confidence::High))
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_scan_range() -> Result<()> {
+        let pat = pattern!("488b05${'}");
+
+        let mut code = vec![0xCC; 0x40];
+
+        code[0x01..0x08].copy_from_slice(&[0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00]);
+        code[0x21..0x28].copy_from_slice(&[0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00]);
+
+        let buf = build_pe(&code);
+        let view = PeView::from_bytes(&buf)?;
+
+        let find = |scan_range| {
+            let mut save = vec![0; save_len(pat)];
+
+            let attrs = PatternAttrs {
+                scan_range,
+                ..PatternAttrs::DEFAULT
+            };
+
+            find_pattern(&view, pat, &attrs, &mut save).map(|confidence| (save[0], confidence))
+        };
+
+        // Both matches are scanned, so the match isn't unique.
+        assert_eq!(find((0, 0)), Some((0x1001, Confidence::Low)));
+        assert_eq!(find((0x1010, 0x1040)), Some((0x1021, Confidence::High)));
+        assert_eq!(find((0, 0x1010)), Some((0x1001, Confidence::High)));
+        assert_eq!(find((0x1030, 0x1040)), None);
+        assert_eq!(find((0x2000, 0x1000)), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_section() -> Result<()> {
+        let buf = build_pe_with(&[
+            (
+                b".text",
+                &[0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00],
+                0x60000020,
+            ),
+            (b".rdata", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x40000040),
+        ]);
+
+        let view = PeView::from_bytes(&buf)?;
+
+        let pat = pattern!("deadbeef");
+        let mut save = vec![0; save_len(pat)];
+
+        let rdata = PatternAttrs {
+            section: ".rdata",
+            ..PatternAttrs::DEFAULT
+        };
+
+        let missing = PatternAttrs {
+            section: ".data",
+            ..PatternAttrs::DEFAULT
+        };
+
+        assert!(find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save).is_none());
+        assert!(find_pattern(&view, pat, &missing, &mut save).is_none());
+
+        // Only `.rdata` is scanned, where the pattern is unique, but a match outside of code has
+        // low confidence nonetheless.
+        assert_eq!(
+            find_pattern(&view, pat, &rdata, &mut save),
+            Some(Confidence::Low)
+        );
+        assert_eq!(save[0], 0x2001);
+
+        // Matches in `.rdata` don't make a pattern of the code section ambiguous, and the other way
+        // around.
+        let buf = build_pe_with(&[
+            (b".text", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x60000020),
+            (b".rdata", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x40000040),
+        ]);
+
+        let view = PeView::from_bytes(&buf)?;
+
+        assert_eq!(
+            find_pattern(&view, pat, &rdata, &mut save),
+            Some(Confidence::Low)
+        );
+        assert_eq!(save[0], 0x2001);
+
+        assert_eq!(
+            find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save),
+            Some(Confidence::High)
+        );
+        assert_eq!(save[0], 0x1001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn executable_sections() -> Result<()> {
+        let pat = pattern!("deadbeef");
+        let mut save = vec![0; save_len(pat)];
+
+        // Every executable section is scanned by default, but not the others.
+        let buf = build_pe_with(&[
+            (b".text", &[0xCC; 4], 0x60000020),
+            (b".rdata", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x40000040),
+            (b".text2", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x60000020),
+        ]);
+
+        let view = PeView::from_bytes(&buf)?;
+
+        assert_eq!(
+            find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save),
+            Some(Confidence::High)
+        );
+        assert_eq!(save[0], 0x3001);
+
+        // Matches in different executable sections make a pattern ambiguous.
+        let buf = build_pe_with(&[
+            (b".text", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x60000020),
+            (b".text2", &[0x00, 0xDE, 0xAD, 0xBE, 0xEF], 0x60000020),
+        ]);
+
+        let view = PeView::from_bytes(&buf)?;
+
+        assert_eq!(
+            find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save),
+            Some(Confidence::Low)
+        );
+        assert_eq!(save[0], 0x1001);
+
+        // The scan range applies to every section.
+        let attrs = PatternAttrs {
+            scan_range: (0x2000, 0x3000),
+            ..PatternAttrs::DEFAULT
+        };
+
+        assert_eq!(
+            find_pattern(&view, pat, &attrs, &mut save),
+            Some(Confidence::High)
+        );
+        assert_eq!(save[0], 0x2001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_entry() -> Result<()> {
+        let buf = build_pe_with_export(&[0xCC; 0x20], "GetTestGlobal", 0x1010);
+
+        let view = PeView::from_bytes(&buf)?;
+
+        assert_eq!(export_rva(&view, "GetTestGlobal"), Some(0x1010));
+        assert_eq!(export_rva(&view, "Missing"), None);
+
+        let attrs = test_patterns::PATTERNS["dwExported"].2;
+
+        assert!(attrs.export);
+        assert_eq!(attrs.source, "GetTestGlobal");
+
+        let map = test_patterns::offsets(view)?;
+
+        assert_eq!(map["dwExported"], 0x1010);
+        assert!(!map.contains_key("dwOld"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_mask_bytes() {
+        let (bytes, mask) = mask_bytes(pattern!("488935${'} 4885f6"));
+
+        assert_eq!(bytes, [0x48, 0x89, 0x35, 0, 0, 0, 0, 0x48, 0x85, 0xF6]);
+        assert_eq!(mask, "xxx????xxx");
+
+        let (bytes, mask) = mask_bytes(pattern!("8b81u4 c3 ?? 48"));
+
+        assert_eq!(bytes, [0x8B, 0x81, 0, 0, 0, 0, 0xC3, 0, 0, 0x48]);
+        assert_eq!(mask, "xx????x??x");
+    }
+
+    #[test]
+    fn rebase_address() {
+        assert_eq!(
+            rebase(0x7FF800001234, 0x7FF800000000, 0x7FFA12340000),
+            Some(0x7FFA12341234)
+        );
+        assert_eq!(rebase(0x1234, 0x1234, 0), Some(0));
+        assert_eq!(rebase(0x1000, 0x2000, 0x3000), None);
+        assert_eq!(rebase(u64::MAX, 0, 1), None);
+    }
+
+    #[test]
+    fn absolute_addresses() {
+        let offsets = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1234),
+                    ("dwEntityList_chunkSize".to_string(), 0x200),
+                    ("dwGameRules".to_string(), 0x2000),
+                    ("dwGameRules_field".to_string(), 0x40),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([("dwBuildNumber".to_string(), 0x5000)]),
+            ),
+        ]);
+
+        let bases = ModuleBases::from([("client.dll".to_string(), 0x7FF800000000)]);
+
+        let table = absolute_table(&offsets, &bases);
+        let lines: Vec<_> = table
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect();
+
+        assert_eq!(lines[0], ["offset", "rva", "address"]);
+        assert_eq!(
+            lines[1],
+            ["client.dll::dwEntityList", "0x1234", "0x00007FF800001234"]
+        );
+        assert_eq!(
+            lines[2],
+            ["client.dll::dwEntityList_chunkSize", "0x200", "-"]
+        );
+        assert_eq!(lines[4], ["client.dll::dwGameRules_field", "0x40", "-"]);
+        assert_eq!(lines[5], ["engine2.dll::dwBuildNumber", "0x5000", "?"]);
+    }
+
+    #[test]
+    fn interface_regs_entry() -> Result<()> {
+        // mov r9, [rip + 0x100]
+        let code = [0x4C, 0x8B, 0x0D, 0x00, 0x01, 0x00, 0x00];
+
+        let buf = build_pe_with_export(&code, "CreateInterface", 0x1000);
+        let map = input_system::offsets(PeView::from_bytes(&buf)?)?;
+
+        assert_eq!(map["dwCreateInterface"], 0x1000);
+        assert_eq!(map["dwInterfaceRegs"], 0x1107);
+
+        Ok(())
+    }
+
+    #[test]
+    fn first_interface_reg() -> Result<()> {
+        let mut process = setup()?;
+
+        let client_base = process.module_by_name("client.dll")?.base;
+
+        let offset = get_offset_value("client.dll", "dwInterfaceRegs").unwrap();
+
+        let reg_ptr = process.read_addr64(client_base + offset).data_part()?;
+        let reg: InterfaceReg = process.read(reg_ptr).data_part()?;
+
+        assert!(!reg.name.is_null());
+
+        let name = process.read_utf8(reg.name.address(), 128).data_part()?;
+
+        println!("first interface: {}", name);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_near_miss() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x8B, 0x05, 0x11, 0x22, 0x33, 0x44]);
+        let view = PeView::from_bytes(&buf)?;
+
+        let pat = pattern!("488b05 11223355");
+
+        assert!(!view.scanner().finds_code(pat, &mut vec![0; save_len(pat)]));
+        assert_eq!(
+            near_miss(&view, pat),
+            Some(NearMiss {
+                rva: 0x1000,
+                matched: 6,
+                mismatch: Some((0x55, 0x44)),
+            })
+        );
+
+        assert_eq!(
+            near_miss(&view, pattern!("488b05 ?? 2233${'}")).map(|miss| miss.matched),
+            Some(6)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_confidence() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x89, 0x5C, 0x24, 0x08, 0x48, 0x89, 0x5C, 0x24, 0x08]);
+        let view = PeView::from_bytes(&buf)?;
+
+        let find = |pat: &[Atom]| {
+            let mut save = vec![0; save_len(pat)];
+
+            find_pattern(&view, pat, &PatternAttrs::DEFAULT, &mut save)
+        };
+
+        assert_eq!(
+            find(pattern!("48895c2408 48895c2408")),
+            Some(Confidence::High)
+        );
+        assert_eq!(find(pattern!("48895c2408")), Some(Confidence::Low));
+        assert_eq!(find(pattern!("48895c2410")), None);
+
+        // The confidence of resolved offsets is recorded by module and name.
+        let buf = build_pe(&[
+            0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00, 0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        let map = test_confidence::offsets(PeView::from_bytes(&buf)?)?;
+
+        assert_eq!(map["dwAmbiguous"], 0x1007);
+        assert_eq!(
+            confidence("test_confidence", "dwAmbiguous"),
+            Some(Confidence::Low)
+        );
+        assert_eq!(
+            confidence("test_confidence", "dwUnique"),
+            Some(Confidence::High)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn signature_mismatch() -> Result<()> {
+        let buf = build_pe(&[0x48, 0x89, 0x5C, 0x24, 0x08]);
+        let view = PeView::from_bytes(&buf)?;
+
+        assert!(verify_signature(&view, "dwTest", 0x1000, &[0x48, 0x89]));
+        assert!(!verify_signature(&view, "dwTest", 0x1000, &[0x40, 0x53]));
+
+        assert_eq!(
+            signature_warning(&view, "dwTest", 0x1000, &[0x48, 0x89]),
+            None
+        );
+        assert_eq!(
+            signature_warning(&view, "dwTest", 0x1000, &[0x40, 0x53]).as_deref(),
+            Some("signature mismatch: dwTest at 0x1000 (expected 40 53, found 48 89)")
+        );
+
+        // The export resolves, but `CreateInterface` doesn't start by loading `s_pInterfaceRegs`.
+        let buf = build_pe_with_export(
+            &[0x40, 0x53, 0x48, 0x83, 0xEC, 0x20],
+            "CreateInterface",
+            0x1000,
+        );
+        let view = PeView::from_bytes(&buf)?;
+
+        assert!(signature_warning(&view, "dwCreateInterface", 0x1000, CREATE_INTERFACE).is_some());
+
+        // The signature isn't fatal, so the offset is still dumped.
+        assert_eq!(input_system::offsets(view)?["dwCreateInterface"], 0x1000);
+
+        Ok(())
+    }
+
     #[test]
     fn window_size() -> Result<()> {
         let mut process = setup()?;
@@ -1,7 +1,7 @@
 use std::collections::BTreeMap;
 use std::ffi::CStr;
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 
 use log::debug;
 
@@ -12,6 +12,8 @@ use pelite::pe64::{Pe, PeView};
 
 use serde::{Deserialize, Serialize};
 
+use super::{DumperError, read_module};
+
 use crate::source2::*;
 
 pub type SchemaMap = BTreeMap<String, (Vec<Class>, Vec<Enum>)>;
@@ -60,7 +62,7 @@ pub struct TypeScope {
     pub enums: Vec<Enum>,
 }
 
-pub fn schemas<P: Process + MemoryView>(process: &mut P) -> Result<SchemaMap> {
+pub fn schemas<P: Process + MemoryView>(process: &mut P) -> Result<SchemaMap, DumperError> {
     let schema_system = read_schema_system(process)?;
     let type_scopes = read_type_scopes(process, &schema_system)?;
 
@@ -80,7 +82,7 @@ pub fn schemas<P: Process + MemoryView>(process: &mut P) -> Result<SchemaMap> {
 fn read_class_binding(
     mem: &mut impl MemoryView,
     binding_ptr: Pointer64<SchemaClassBinding>,
-) -> Result<Class> {
+) -> Result<Class, DumperError> {
     let binding = mem.read_ptr(binding_ptr).data_part()?;
 
     let module_name = mem
@@ -93,7 +95,7 @@ fn read_class_binding(
         .data_part()?;
 
     if name.is_empty() {
-        bail!("empty class name");
+        return Err(DumperError::InvalidData("empty class name"));
     }
 
     let parent = binding.base_classes.non_null().and_then(|ptr| {
@@ -144,7 +146,7 @@ fn read_class_binding(
 fn read_class_binding_fields(
     mem: &mut impl MemoryView,
     binding: &SchemaClassBinding,
-) -> Result<Vec<ClassField>> {
+) -> Result<Vec<ClassField>, DumperError> {
     if binding.fields.is_null() {
         return Ok(Vec::new());
     }
@@ -181,7 +183,7 @@ fn read_class_binding_fields(
 fn read_class_binding_metadata(
     mem: &mut impl MemoryView,
     binding: &SchemaClassBinding,
-) -> Result<Vec<ClassMetadata>> {
+) -> Result<Vec<ClassMetadata>, DumperError> {
     if binding.static_metadata.is_null() {
         return Ok(Vec::new());
     }
@@ -235,7 +237,7 @@ fn read_class_binding_metadata(
 fn read_enum_binding(
     mem: &mut impl MemoryView,
     binding_ptr: Pointer64<SchemaEnumBinding>,
-) -> Result<Enum> {
+) -> Result<Enum, DumperError> {
     let binding = mem.read_ptr(binding_ptr).data_part()?;
 
     let name = mem
@@ -243,7 +245,7 @@ fn read_enum_binding(
         .data_part()?;
 
     if name.is_empty() {
-        bail!("empty enum name");
+        return Err(DumperError::InvalidData("empty enum name"));
     }
 
     let members = read_enum_binding_members(mem, &binding)?;
@@ -267,7 +269,7 @@ fn read_enum_binding(
 fn read_enum_binding_members(
     mem: &mut impl MemoryView,
     binding: &SchemaEnumBinding,
-) -> Result<Vec<EnumMember>> {
+) -> Result<Vec<EnumMember>, DumperError> {
     if binding.enums.is_null() {
         return Ok(Vec::new());
     }
@@ -288,14 +290,17 @@ fn read_enum_binding_members(
     })
 }
 
-fn read_schema_system<P: Process + MemoryView>(process: &mut P) -> Result<SchemaSystem> {
-    let module = process.module_by_name("schemasystem.dll")?;
+fn read_schema_system<P: Process + MemoryView>(
+    process: &mut P,
+) -> Result<SchemaSystem, DumperError> {
+    let module = process
+        .module_by_name("schemasystem.dll")
+        .map_err(|_| DumperError::ModuleNotFound("schemasystem.dll".to_string()))?;
 
-    let buf = process
-        .read_raw(module.base, module.size as _)
-        .data_part()?;
+    let buf = read_module(process, &module)?;
 
-    let view = PeView::from_bytes(&buf)?;
+    let view = PeView::from_bytes(&buf)
+        .map_err(|source| DumperError::invalid_image(module.name.as_ref(), &buf, source))?;
 
     let mut save = [0; 2];
 
@@ -303,13 +308,15 @@ fn read_schema_system<P: Process + MemoryView>(process: &mut P) -> Result<Schema
         .scanner()
         .finds_code(pattern!("4c8d35${'} 0f2845"), &mut save)
     {
-        bail!("outdated schema system pattern");
+        return Err(DumperError::OutdatedPattern("schema system"));
     }
 
     let schema_system: SchemaSystem = process.read(module.base + save[1]).data_part()?;
 
     if schema_system.num_registrations == 0 {
-        bail!("no schema system registrations found");
+        return Err(DumperError::InvalidData(
+            "no schema system registrations found",
+        ));
     }
 
     Ok(schema_system)
@@ -318,7 +325,7 @@ fn read_schema_system<P: Process + MemoryView>(process: &mut P) -> Result<Schema
 fn read_type_scopes(
     mem: &mut impl MemoryView,
     schema_system: &SchemaSystem,
-) -> Result<Vec<TypeScope>> {
+) -> Result<Vec<TypeScope>, DumperError> {
     let type_scopes = &schema_system.type_scopes;
 
     (0..type_scopes.size).try_fold(Vec::new(), |mut acc, i| {
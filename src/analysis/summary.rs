@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use super::{AnalysisResult, OffsetMap, catalog, pattern_attrs};
+
+/// A short report of a dump, printed once it's done.
+#[derive(Debug)]
+pub struct Summary {
+    /// The number of offsets found per module.
+    pub offsets: BTreeMap<String, usize>,
+    /// The number of patterns that didn't match. See [`outdated_patterns`].
+    pub outdated: usize,
+    /// The number of patterns that matched, but whose callbacks failed.
+    pub failed_callbacks: usize,
+    pub build_number: Option<u32>,
+    pub elapsed: Duration,
+}
+
+impl Summary {
+    pub fn new(
+        result: &AnalysisResult,
+        outdated: usize,
+        build_number: Option<u32>,
+        elapsed: Duration,
+    ) -> Self {
+        let offsets = result
+            .offsets
+            .iter()
+            .map(|(module_name, offsets)| (module_name.clone(), offsets.len()))
+            .collect();
+
+        let failed_callbacks = result
+            .failed_callbacks
+            .values()
+            .map(|names| names.len())
+            .sum();
+
+        Self {
+            offsets,
+            outdated,
+            failed_callbacks,
+            build_number,
+            elapsed,
+        }
+    }
+}
+
+/// Returns the number of patterns that didn't match in the modules of `offsets`, which must be the
+/// offsets as they were resolved, before `--feature` or `--exclude-offsets` left any out. Optional
+/// offsets and modules that couldn't be analyzed at all aren't counted.
+pub fn outdated_patterns(offsets: &OffsetMap) -> usize {
+    catalog()
+        .into_iter()
+        .filter(|&(module_name, name, _)| {
+            offsets
+                .get(module_name)
+                .is_some_and(|offsets| !offsets.contains_key(name))
+                && !pattern_attrs(module_name, name).is_some_and(|attrs| attrs.optional)
+        })
+        .count()
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let build_number = self.build_number.map_or_else(
+            || "unknown".to_string(),
+            |build_number| build_number.to_string(),
+        );
+
+        writeln!(f, "build number: {}", build_number)?;
+
+        writeln!(
+            f,
+            "offsets: {} across {} modules",
+            self.offsets.values().sum::<usize>(),
+            self.offsets.len()
+        )?;
+
+        for (module_name, count) in &self.offsets {
+            writeln!(f, "  {}: {}", module_name, count)?;
+        }
+
+        writeln!(f, "outdated patterns: {}", self.outdated)?;
+        writeln!(f, "failed callbacks: {}", self.failed_callbacks)?;
+
+        write!(f, "elapsed: {:.2?}", self.elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let result = AnalysisResult {
+            offsets: BTreeMap::from([
+                (
+                    "client.dll".to_string(),
+                    BTreeMap::from([
+                        ("dwEntityList".to_string(), 0x1234),
+                        ("dwViewMatrix".to_string(), 0x5678),
+                    ]),
+                ),
+                (
+                    "engine2.dll".to_string(),
+                    BTreeMap::from([("dwBuildNumber".to_string(), 0x9ABC)]),
+                ),
+            ]),
+            failed_callbacks: BTreeMap::from([(
+                "client.dll".to_string(),
+                ["dwCSGOInput".to_string()].into(),
+            )]),
+            ..Default::default()
+        };
+
+        let summary = Summary::new(&result, 2, Some(14000), Duration::from_millis(1500));
+
+        let out = summary.to_string();
+        let lines: Vec<_> = out.lines().collect();
+
+        assert_eq!(lines[0], "build number: 14000");
+        assert_eq!(lines[1], "offsets: 3 across 2 modules");
+        assert_eq!(lines[2], "  client.dll: 2");
+        assert_eq!(lines[3], "  engine2.dll: 1");
+        assert_eq!(lines[4], "outdated patterns: 2");
+        assert_eq!(lines[5], "failed callbacks: 1");
+        assert_eq!(lines[6], "elapsed: 1.50s");
+    }
+
+    #[test]
+    fn outdated_before_filtering() {
+        let mut offsets = OffsetMap::new();
+
+        for (module_name, name, _) in catalog() {
+            offsets
+                .entry(module_name.to_string())
+                .or_default()
+                .insert(name.to_string(), 0x1000);
+        }
+
+        assert_eq!(outdated_patterns(&offsets), 0);
+
+        offsets
+            .get_mut("client.dll")
+            .unwrap()
+            .remove("dwEntityList");
+
+        assert_eq!(outdated_patterns(&offsets), 1);
+
+        // Modules that couldn't be analyzed aren't counted as outdated patterns.
+        offsets.remove("client.dll");
+
+        assert_eq!(outdated_patterns(&offsets), 0);
+    }
+}
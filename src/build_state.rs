@@ -0,0 +1,65 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Returns the build number stored in the state file at `path`, or `None` if there's none yet.
+pub fn last_seen(path: &Path) -> Result<Option<u32>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("unable to read {}", path.display()));
+        }
+    };
+
+    let build_number = content
+        .trim()
+        .parse()
+        .with_context(|| format!("invalid build number in {}", path.display()))?;
+
+    Ok(Some(build_number))
+}
+
+/// Returns `true` if `build_number` is known and matches the one stored at `path`.
+pub fn is_unchanged(path: &Path, build_number: Option<u32>) -> Result<bool> {
+    let Some(build_number) = build_number else {
+        return Ok(false);
+    };
+
+    Ok(last_seen(path)? == Some(build_number))
+}
+
+/// Stores `build_number` as the last-seen build number at `path`.
+pub fn save(path: &Path, build_number: u32) -> Result<()> {
+    fs::write(path, format!("{}\n", build_number))
+        .with_context(|| format!("unable to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    #[test]
+    fn unchanged_build() -> Result<()> {
+        let path = env::temp_dir().join(format!("cs2-dumper-build-{}", std::process::id()));
+
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(last_seen(&path)?, None);
+        assert!(!is_unchanged(&path, Some(14000))?);
+
+        save(&path, 14000)?;
+
+        assert!(is_unchanged(&path, Some(14000))?);
+        assert!(!is_unchanged(&path, Some(14001))?);
+        assert!(!is_unchanged(&path, None)?);
+
+        fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use serde_json::Value;
+
+use crate::analysis::OffsetMap;
+
+/// How a single offset changed since the baseline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(u32),
+    Removed(u32),
+    Changed { old: u32, new: u32 },
+}
+
+/// The changes of each module, by module and offset name. Unchanged offsets are left out.
+pub type Changes = BTreeMap<String, BTreeMap<String, Change>>;
+
+/// Reads the offsets from a previously generated `offsets.json`, or returns `None` if there's none.
+pub fn read_baseline(path: &Path) -> Result<Option<OffsetMap>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("unable to read {}", path.display()));
+        }
+    };
+
+    let value: BTreeMap<String, Value> = serde_json::from_str(&content)
+        .with_context(|| format!("invalid offsets in {}", path.display()))?;
+
+    let offsets = value
+        .into_iter()
+        .filter_map(|(module_name, offsets)| {
+            // Skips top-level keys that aren't modules, such as `schema_version`.
+            let offsets = offsets
+                .as_object()?
+                .iter()
+                .filter_map(|(name, value)| {
+                    // Deprecated offsets are written as `{"value": ..., "deprecated": true}`, and
+                    // signed ones as negative numbers, which are kept as the same 32 bits.
+                    let value = value.get("value").unwrap_or(value).as_i64()?;
+
+                    Some((name.clone(), value as u32))
+                })
+                .collect();
+
+            Some((module_name, offsets))
+        })
+        .collect();
+
+    Ok(Some(offsets))
+}
+
+/// Compares `current` with `baseline`. Without a baseline, every offset is added.
+pub fn diff(baseline: Option<&OffsetMap>, current: &OffsetMap) -> Changes {
+    let empty = OffsetMap::new();
+    let baseline = baseline.unwrap_or(&empty);
+
+    let mut changes = Changes::new();
+
+    let module_names: BTreeSet<_> = baseline.keys().chain(current.keys()).collect();
+
+    for module_name in module_names {
+        let old = baseline.get(module_name);
+        let new = current.get(module_name);
+
+        let names: BTreeSet<_> = old
+            .into_iter()
+            .chain(new)
+            .flat_map(|offsets| offsets.keys())
+            .collect();
+
+        for name in names {
+            let change = match (
+                old.and_then(|offsets| offsets.get(name)),
+                new.and_then(|offsets| offsets.get(name)),
+            ) {
+                (None, Some(&new)) => Change::Added(new),
+                (Some(&old), None) => Change::Removed(old),
+                (Some(&old), Some(&new)) if old != new => Change::Changed { old, new },
+                _ => continue,
+            };
+
+            changes
+                .entry(module_name.clone())
+                .or_default()
+                .insert(name.clone(), change);
+        }
+    }
+
+    changes
+}
+
+/// Renders `changes` as Markdown for release notes, one section per module.
+pub fn render(changes: &Changes, has_baseline: bool) -> String {
+    let mut out = String::from("# Offset Changes\n");
+
+    if !has_baseline {
+        out.push_str("\nNo previous offsets were found, so all offsets are listed as added.\n");
+    }
+
+    if changes.is_empty() {
+        out.push_str("\nNo offsets changed.\n");
+
+        return out;
+    }
+
+    for (module_name, changes) in changes {
+        let _ = write!(out, "\n## {}\n\n", module_name);
+
+        for (name, change) in changes {
+            let _ = match change {
+                Change::Added(new) => writeln!(out, "- Added `{}`: `{:#X}`", name, new),
+                Change::Removed(old) => writeln!(out, "- Removed `{}` (was `{:#X}`)", name, old),
+                Change::Changed { old, new } => {
+                    writeln!(out, "- Changed `{}`: `{:#X}` -> `{:#X}`", name, old, new)
+                }
+            };
+        }
+    }
+
+    out
+}
+
+/// Writes a summary of how `current` differs from `baseline` to `path`.
+pub fn write(path: &Path, baseline: Option<&OffsetMap>, current: &OffsetMap) -> Result<()> {
+    let content = render(&diff(baseline, current), baseline.is_some());
+
+    fs::write(path, content).with_context(|| format!("unable to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    #[test]
+    fn changes_summary() -> Result<()> {
+        let path = env::temp_dir().join(format!("cs2-dumper-baseline-{}.json", std::process::id()));
+
+        fs::write(
+            &path,
+            r#"{
+                "client.dll": {
+                    "dwEntityList": 4096,
+                    "dwGameRules": {"value": 8192, "deprecated": true},
+                    "dwViewMatrix": 12288
+                },
+                "engine2.dll": {
+                    "dwNetworkGameClient_localPlayer": -16
+                },
+                "schema_version": 1
+            }"#,
+        )?;
+
+        let baseline = read_baseline(&path)?.unwrap();
+
+        fs::remove_file(&path)?;
+
+        assert!(read_baseline(&path)?.is_none());
+
+        let current = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1010),
+                    ("dwGameRules".to_string(), 0x2000),
+                    ("dwGlobalVars".to_string(), 0x4000),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([
+                    ("dwBuildNumber".to_string(), 0x5000),
+                    ("dwNetworkGameClient_localPlayer".to_string(), 0xFFFFFFF0),
+                ]),
+            ),
+        ]);
+
+        assert_eq!(
+            baseline["engine2.dll"]["dwNetworkGameClient_localPlayer"],
+            0xFFFFFFF0
+        );
+
+        let expected = [
+            "# Offset Changes",
+            "",
+            "## client.dll",
+            "",
+            "- Changed `dwEntityList`: `0x1000` -> `0x1010`",
+            "- Added `dwGlobalVars`: `0x4000`",
+            "- Removed `dwViewMatrix` (was `0x3000`)",
+            "",
+            "## engine2.dll",
+            "",
+            "- Added `dwBuildNumber`: `0x5000`",
+        ];
+
+        let out = render(&diff(Some(&baseline), &current), true);
+
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+
+        let out = render(&diff(None, &current), false);
+
+        assert!(out.contains("all offsets are listed as added"));
+        assert!(out.contains("- Added `dwEntityList`: `0x1010`"));
+
+        assert_eq!(
+            render(&diff(Some(&current), &current), true),
+            "# Offset Changes\n\nNo offsets changed.\n"
+        );
+
+        Ok(())
+    }
+}
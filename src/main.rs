@@ -2,25 +2,32 @@
 #![allow(unused_imports)]
 
 use std::fs::File;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Instant;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Parser, Subcommand};
 
-use log::{LevelFilter, info};
+use log::{LevelFilter, error, info, warn};
 
 use memflow::prelude::v1::*;
 
 use simplelog::*;
 
-use output::Output;
+use analysis::AnalysisResult;
+
+use output::{ConstantCase, FormatOptions, HexCase, Output, PartialWriter, Radix, SortBy};
 
 mod analysis;
+mod build_state;
+mod changelog;
 mod output;
+mod pipe;
 mod source2;
+mod verify;
 
 #[derive(Debug, Parser)]
 #[command(author, version)]
@@ -29,22 +36,180 @@ struct Args {
     #[arg(short, long)]
     connector: Option<String>,
 
-    /// Additional arguments to pass to the memflow connector.
-    #[arg(short = 'a', long)]
-    connector_args: Option<String>,
+    /// Additional arguments to pass to the memflow connector, as `[target][:key=value,...]`.
+    #[arg(short = 'a', long, requires = "connector", value_parser = parse_connector_args)]
+    connector_args: Option<ConnectorArgs>,
+
+    /// The size of the page cache of the connector, in bytes. Enables caching.
+    #[arg(long, requires = "connector")]
+    cache_size: Option<usize>,
+
+    /// How long cached pages stay valid, in milliseconds. Enables caching.
+    #[arg(long, requires = "connector")]
+    cache_validity: Option<u64>,
+
+    /// Write a CHANGES.md summarizing how the offsets changed since the offsets.json in the output
+    /// directory.
+    #[arg(long)]
+    changelog: bool,
+
+    /// Skip the dump if the game's build number matches the one stored in this file, and store
+    /// it there after each dump.
+    #[arg(long, conflicts_with = "from_disk")]
+    compare_build: Option<PathBuf>,
+
+    /// How names are converted to upper snake case, such as for the variables in env files.
+    #[arg(long, value_enum, default_value_t = ConstantCase::Acronyms)]
+    constant_case: ConstantCase,
+
+    /// Write a hash of the generated content to each file, which doesn't change with the timestamp.
+    #[arg(long)]
+    content_hash: bool,
+
+    /// Log every capture slot of each pattern match, not only the resolved one. Requires `-vvv`.
+    #[arg(long)]
+    debug_captures: bool,
+
+    /// Resolve offsets from the module files in these directories instead of a running process.
+    #[arg(long, value_delimiter = ',')]
+    from_disk: Vec<PathBuf>,
+
+    /// Write the first instructions at each pattern match as comments above the offsets.
+    #[cfg(feature = "disasm")]
+    #[arg(long, conflicts_with = "from_disk")]
+    disasm: bool,
+
+    /// A JSON file mapping pattern modules to the DLLs they're resolved in, overriding the
+    /// built-in DLL names.
+    #[arg(long)]
+    dll_names: Option<PathBuf>,
+
+    /// Dump even if the build number hasn't changed since the last dump.
+    #[arg(long, requires = "compare_build")]
+    force: bool,
+
+    /// Leave out offsets whose names match these patterns, which may contain `*` and `?`
+    /// wildcards.
+    #[arg(long, value_delimiter = ',')]
+    exclude_offsets: Vec<String>,
+
+    /// Fail if any offset that isn't marked as optional couldn't be resolved.
+    #[arg(long)]
+    fail_on_missing: bool,
+
+    /// Only write the offsets tagged with this game feature, such as `rendering`.
+    #[arg(long)]
+    feature: Option<String>,
 
     /// The types of files to generate.
     #[arg(short, long, value_delimiter = ',', default_values = ["cs", "hpp", "json", "rs"])]
     file_types: Vec<String>,
 
+    /// The file type to write when using `--stdout`.
+    #[arg(long, default_value = "json", requires = "stdout")]
+    format: String,
+
+    /// The case of the digits in hex literals.
+    #[arg(long, value_enum, default_value_t = HexCase::Upper)]
+    hex_case: HexCase,
+
+    /// The minimum number of digits in hex literals.
+    #[arg(long, default_value_t = 0)]
+    hex_width: usize,
+
+    /// Annotate each offset in the C++ files with its absolute address at the current module base.
+    #[arg(long, conflicts_with = "from_disk")]
+    include_abs: bool,
+
     /// The number of spaces to use per indentation level.
     #[arg(short, long, default_value_t = 4)]
     indent_size: usize,
 
+    /// The index of the process to dump if several are named `--process-name`, in the order they're
+    /// listed.
+    #[arg(long, conflicts_with_all = ["pid", "from_disk"])]
+    instance: Option<usize>,
+
+    /// Keep modules without any offsets in the generated files.
+    #[arg(long)]
+    keep_empty_modules: bool,
+
+    /// Write a manifest.json with the size and SHA-256 of every generated file.
+    #[arg(long)]
+    manifest: bool,
+
+    /// Warn about patterns with fewer than this many non-wildcard bytes.
+    #[arg(long)]
+    min_pattern_len: Option<usize>,
+
+    /// A file listing additional DLLs to scan, one per line, with their patterns as
+    /// `dll:name=<pattern>`.
+    #[arg(long)]
+    modules_from_file: Option<PathBuf>,
+
+    /// Replace a built-in pattern, as `module:name=<pattern>`. Can be specified multiple times.
+    #[arg(long, value_name = "MODULE:NAME=PATTERN")]
+    override_pattern: Vec<String>,
+
     /// The output directory to write the generated files to.
     #[arg(short, long, default_value = "output")]
     output: PathBuf,
 
+    /// Print each offset with its RVA and absolute address in the process instead of generating
+    /// files.
+    #[arg(long, conflicts_with_all = ["stdout", "from_disk"])]
+    print_absolute: bool,
+
+    /// The PID of the process to dump if several are named `--process-name`.
+    #[arg(long, conflicts_with = "from_disk")]
+    pid: Option<Pid>,
+
+    /// Separate the bytes of the patterns in the generated files with spaces, for readability.
+    #[arg(long)]
+    pretty_patterns: bool,
+
+    /// Transform an offset in the generated files, as `module:name=<transform>`, such as
+    /// `client.dll:dwEntityList=div:8`. Can be specified multiple times.
+    #[arg(long, value_name = "MODULE:NAME=TRANSFORM")]
+    transform: Vec<String>,
+
+    /// Also write the JSON of each dump to this named pipe, if something is reading from it.
+    #[arg(long)]
+    pipe: Option<PathBuf>,
+
+    /// The radix of the offsets in the generated code files.
+    #[arg(long, value_enum, default_value_t = Radix::Hex)]
+    radix: Radix,
+
+    /// Read back this many randomly sampled offsets from the process after scanning, and fail if
+    /// any of them can't be read.
+    #[arg(long, conflicts_with_all = ["stdout", "from_disk"])]
+    sample_size: Option<usize>,
+
+    /// The seed used to sample offsets for `--sample-size`.
+    #[arg(long, default_value_t = 0, requires = "sample_size")]
+    sample_seed: u64,
+
+    /// Fail if the build number can't be read, instead of leaving it out of info.json.
+    #[arg(long, conflicts_with = "from_disk")]
+    require_build: bool,
+
+    /// Replace the patterns in the generated files with a placeholder, and leave out the mask files.
+    #[arg(long)]
+    redact: bool,
+
+    /// The order in which to write entries to the generated code files.
+    #[arg(long, value_enum, default_value_t = SortBy::Name)]
+    sort_by: SortBy,
+
+    /// Abort on the first failed analysis instead of skipping it.
+    #[arg(long)]
+    strict: bool,
+
+    /// Write a single file type to standard output instead of generating files.
+    #[arg(long)]
+    stdout: bool,
+
     /// The name of the game process.
     #[arg(short, long, default_value = "cs2.exe")]
     process_name: String,
@@ -56,6 +221,28 @@ struct Args {
     /// Prevent creation of the cs2-dumper.log file.
     #[arg(short, long)]
     no_log_file: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the supported file types with their extensions instead of generating files.
+    ListFormats,
+
+    /// Read a few known values from the game with the dumped offsets instead of generating files.
+    VerifyLive,
+
+    /// Print the RVA of every occurrence of a literal byte sequence in a module instead of
+    /// generating files, to re-derive patterns that no longer match.
+    FindBytes {
+        /// The module to scan, such as `client.dll`.
+        module: String,
+
+        /// The bytes to find, in hex, such as `48 8b 05`. Wildcards aren't supported.
+        bytes: String,
+    },
 }
 
 fn main() -> Result<()> {
@@ -69,10 +256,17 @@ fn main() -> Result<()> {
         _ => LevelFilter::Trace,
     };
 
+    // Keep standard output clean when it's used for the generated code.
+    let terminal_mode = if args.stdout || args.print_absolute {
+        TerminalMode::Stderr
+    } else {
+        TerminalMode::Mixed
+    };
+
     let mut loggers: Vec<Box<dyn SharedLogger>> = vec![TermLogger::new(
         level_filter,
         Config::default(),
-        TerminalMode::Mixed,
+        terminal_mode,
         ColorChoice::Auto,
     )];
 
@@ -87,10 +281,119 @@ fn main() -> Result<()> {
 
     CombinedLogger::init(loggers)?;
 
-    let conn_args = args
-        .connector_args
-        .map(|s| ConnectorArgs::from_str(&s).expect("unable to parse connector arguments"))
-        .unwrap_or_default();
+    if let Some(Command::ListFormats) = args.command {
+        for format in output::FORMATS {
+            println!(
+                "{:<14} {:<12} {}",
+                format.name,
+                format.extension(),
+                format.description
+            );
+        }
+
+        return Ok(());
+    }
+
+    if let Some(path) = &args.dll_names {
+        analysis::load_dll_names(path)?;
+    }
+
+    analysis::load_pattern_overrides(&args.override_pattern)?;
+
+    let mut registry = analysis::Registry::default();
+
+    if let Some(path) = &args.modules_from_file {
+        registry
+            .load_module_list(path)
+            .with_context(|| format!("invalid module list: {}", path.display()))?;
+    }
+
+    analysis::check_duplicate_names();
+    analysis::check_duplicate_patterns();
+    analysis::set_debug_captures(args.debug_captures);
+    analysis::validate_globs(&args.exclude_offsets)?;
+
+    let transforms = analysis::parse_transforms(&args.transform)?;
+
+    if let Some(min_len) = args.min_pattern_len {
+        analysis::check_pattern_lengths(min_len);
+    }
+
+    let options = FormatOptions {
+        indent_size: args.indent_size,
+        sort_by: args.sort_by,
+        radix: args.radix,
+        hex_width: args.hex_width,
+        hex_case: args.hex_case,
+        constant_case: args.constant_case,
+        skip_empty_modules: !args.keep_empty_modules,
+        content_hash: args.content_hash,
+        pretty_patterns: args.pretty_patterns,
+        redact_patterns: args.redact,
+    };
+
+    if args.redact && args.file_types.iter().any(|file_type| file_type == "mask") {
+        warn!("mask files consist of the patterns and aren't written with --redact");
+    }
+
+    if !args.from_disk.is_empty() {
+        check_from_disk_command(args.command.as_ref())?;
+
+        registry.check_listed_modules(|dll_name| {
+            args.from_disk
+                .iter()
+                .any(|dir| dir.join(dll_name).is_file())
+        })?;
+
+        let mut result = AnalysisResult {
+            offsets: analysis::offsets_from_disk(
+                &registry,
+                &registry.module_names(),
+                &args.from_disk,
+                args.strict,
+            )?,
+            failed_callbacks: analysis::failed_callbacks(),
+            transforms,
+            patterns: registry.patterns(),
+            ..Default::default()
+        };
+
+        analysis::add_constants(&mut result.offsets);
+
+        if args.fail_on_missing {
+            analysis::check_missing(&result.offsets)?;
+        }
+
+        result.offsets = select_offsets(
+            &result.offsets,
+            args.feature.as_deref(),
+            &args.exclude_offsets,
+        );
+
+        let output = Output::new(&args.file_types, options, &args.output, &result)?;
+
+        if args.stdout {
+            return output.write_all(&args.format, &mut io::stdout().lock());
+        }
+
+        let baseline = read_baseline(args.changelog, &args.output)?;
+
+        output.dump_offsets()?;
+
+        if let Some(path) = &args.pipe {
+            write_pipe(path, &output)?;
+        }
+
+        if args.manifest {
+            output.write_manifest()?;
+        }
+
+        return write_changelog(args.changelog, &args.output, baseline, &result);
+    }
+
+    let mut conn_args = args.connector_args.clone().unwrap_or_default();
+
+    apply_cache_args(&mut conn_args, args.cache_size, args.cache_validity);
 
     let mut os = match args.connector {
         Some(conn) => {
@@ -101,7 +404,8 @@ fn main() -> Result<()> {
                 .connector(&conn)
                 .args(conn_args)
                 .os("win32")
-                .build()?
+                .build()
+                .with_context(|| format!("unable to create connector: {}", conn))?
         }
         None => {
             #[cfg(windows)]
@@ -115,16 +419,576 @@ fn main() -> Result<()> {
         }
     };
 
-    let mut process = os.process_by_name(&args.process_name)?;
+    let candidates: Vec<_> = os
+        .process_info_list()?
+        .into_iter()
+        .filter(|info| {
+            info.name.as_ref() == args.process_name && !matches!(info.state, ProcessState::Dead(_))
+        })
+        .collect();
+
+    let pids: Vec<_> = candidates.iter().map(|info| info.pid).collect();
+
+    let index = select_instance(&args.process_name, &pids, args.instance, args.pid)?;
+
+    let mut process = os
+        .process_by_info(candidates[index].clone())
+        .with_context(|| format!("unable to open process: {}", args.process_name))?;
+
+    registry.check_listed_modules(|dll_name| process.module_by_name(dll_name).is_ok())?;
+
+    if let Some(Command::FindBytes { module, bytes }) = &args.command {
+        let bytes = analysis::parse_bytes(bytes)?;
+
+        let module = process.module_by_name(module)?;
+        let image = analysis::read_module(&mut process, &module)?;
+
+        let rvas = analysis::find_bytes(&image, &bytes);
+
+        info!("found {} occurrences in {}", rvas.len(), module.name);
+
+        for rva in rvas {
+            println!("{:#X}", rva);
+        }
+
+        return Ok(());
+    }
 
     let now = Instant::now();
 
-    let result = analysis::analyze_all(&mut process)?;
-    let output = Output::new(&args.file_types, args.indent_size, &args.output, &result)?;
+    let mut build_number = None;
+
+    if let Some(path) = &args.compare_build {
+        build_number = analysis::read_build_number(&mut process);
+
+        if !args.force && build_state::is_unchanged(path, build_number)? {
+            info!("build number unchanged, skipping dump (use --force to dump anyway)");
+
+            return Ok(());
+        }
+    }
+
+    // Keep the offsets of finished modules on disk in case the dump doesn't complete. The file is
+    // only removed after a complete dump, so it's also left behind if a later step fails.
+    let partial = match (&args.command, args.stdout || args.print_absolute) {
+        (None, false) => Some(PartialWriter::new(&args.output)?),
+        _ => None,
+    };
+
+    let mut result = analysis::analyze_all(
+        &mut process,
+        &registry,
+        args.strict,
+        |module_name, offsets| {
+            let Some(partial) = &partial else {
+                return;
+            };
+
+            let offsets = analysis::OffsetMap::from([(module_name.to_string(), offsets.clone())]);
+            let offsets = select_offsets(&offsets, args.feature.as_deref(), &args.exclude_offsets);
 
-    output.dump_all(&mut process)?;
+            let Some(offsets) = offsets.get(module_name) else {
+                return;
+            };
+
+            if let Err(err) = partial.write_module(module_name, offsets) {
+                error!(
+                    "failed to write partial offsets of {}: {}",
+                    module_name, err
+                );
+            }
+        },
+    )?;
+
+    #[cfg(feature = "disasm")]
+    if args.disasm {
+        result.disasm = analysis::disasm(&mut process, 3)?;
+    }
 
-    info!("analysis completed in {:.2?}", now.elapsed());
+    if args.fail_on_missing {
+        analysis::check_missing(&result.offsets)?;
+    }
+
+    // Read before the offsets are filtered, which may leave out `dwBuildNumber`.
+    let build_number =
+        build_number.or_else(|| analysis::build_number(&mut process, &result.offsets));
+
+    check_build_number(args.require_build, build_number)?;
+
+    // Counted before the offsets are filtered, so that those left out on purpose aren't reported.
+    let outdated = analysis::outdated_patterns(&result.offsets);
+
+    result.offsets = select_offsets(
+        &result.offsets,
+        args.feature.as_deref(),
+        &args.exclude_offsets,
+    );
+
+    result.transforms = transforms;
+
+    if args.include_abs || args.print_absolute {
+        result.module_bases = analysis::module_bases(&mut process, &result.offsets);
+    }
+
+    if args.print_absolute {
+        print!(
+            "{}",
+            analysis::absolute_table(&result.offsets, &result.module_bases)
+        );
+
+        return Ok(());
+    }
+
+    if let Some(sample_size) = args.sample_size {
+        let checks = verify::verify_sample(&mut process, &result, sample_size, args.sample_seed);
+
+        verify::print_summary(&checks)?;
+    }
+
+    if let Some(Command::VerifyLive) = args.command {
+        let checks = verify::verify_live(&mut process, &result);
+
+        return verify::print_summary(&checks);
+    }
+
+    let output = Output::new(&args.file_types, options, &args.output, &result)?;
+
+    if args.stdout {
+        output.write_all(&args.format, &mut io::stdout().lock())?;
+    } else {
+        let baseline = read_baseline(args.changelog, &args.output)?;
+
+        output.dump_all(build_number)?;
+
+        if let Some(path) = &args.pipe {
+            write_pipe(path, &output)?;
+        }
+
+        if args.manifest {
+            output.write_manifest()?;
+        }
+
+        write_changelog(args.changelog, &args.output, baseline, &result)?;
+    }
+
+    if let Some(partial) = partial {
+        partial.finish()?;
+    }
+
+    if let (Some(path), Some(build_number)) = (&args.compare_build, build_number) {
+        build_state::save(path, build_number)?;
+    }
+
+    let summary = analysis::Summary::new(&result, outdated, build_number, now.elapsed());
+
+    // Keep standard output clean when it's used for the generated code.
+    if args.stdout {
+        eprintln!("{}", summary);
+    } else {
+        println!("{}", summary);
+    }
+
+    Ok(())
+}
+
+/// Writes the same JSON as `--stdout` to the named pipe at `path`, unless nothing is reading from it.
+fn write_pipe(path: &Path, output: &Output) -> Result<()> {
+    let Some(mut pipe) = pipe::open(path)? else {
+        warn!("nothing is reading from {}, skipping", path.display());
+
+        return Ok(());
+    };
+
+    output.write_all("json", &mut pipe)
+}
+
+/// Reads the offsets of the previous dump for `--changelog`, before they're overwritten.
+fn read_baseline(enabled: bool, out_dir: &Path) -> Result<Option<analysis::OffsetMap>> {
+    if !enabled {
+        return Ok(None);
+    }
+
+    changelog::read_baseline(&out_dir.join("offsets.json"))
+}
+
+fn write_changelog(
+    enabled: bool,
+    out_dir: &Path,
+    baseline: Option<analysis::OffsetMap>,
+    result: &AnalysisResult,
+) -> Result<()> {
+    if !enabled {
+        return Ok(());
+    }
+
+    // The baseline is read from the generated files, so it holds the transformed values.
+    changelog::write(
+        &out_dir.join("CHANGES.md"),
+        baseline.as_ref(),
+        &analysis::apply_transforms(&result.offsets, &result.transforms),
+    )
+}
+
+/// Leaves out the offsets that aren't tagged with `feature`, if set, and those matching any of
+/// `excluded`.
+fn select_offsets(
+    offsets: &analysis::OffsetMap,
+    feature: Option<&str>,
+    excluded: &[String],
+) -> analysis::OffsetMap {
+    let offsets = match feature {
+        Some(feature) => analysis::filter_by_feature(offsets, feature),
+        None => offsets.clone(),
+    };
+
+    if excluded.is_empty() {
+        return offsets;
+    }
+
+    analysis::exclude_offsets(&offsets, excluded)
+}
+
+/// Fails for subcommands that need a running process, which `--from-disk` doesn't have.
+fn check_from_disk_command(command: Option<&Command>) -> Result<()> {
+    match command {
+        Some(Command::VerifyLive) => bail!("verify-live needs a running process, not --from-disk"),
+        Some(Command::FindBytes { .. }) => {
+            bail!("find-bytes needs a running process, not --from-disk")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails if the build number couldn't be read and `required` is set, such as for archives that are
+/// labeled with it. Otherwise, an unknown build number is only left out of the generated files.
+fn check_build_number(required: bool, build_number: Option<u32>) -> Result<()> {
+    if required && build_number.is_none() {
+        bail!("unable to read the build number, which is required by --require-build");
+    }
 
     Ok(())
 }
+
+/// Enables the page cache of the connector with the given settings, if any are set. Unset settings
+/// keep memflow's defaults.
+fn apply_cache_args(conn_args: &mut ConnectorArgs, size: Option<usize>, validity_ms: Option<u64>) {
+    if size.is_none() && validity_ms.is_none() {
+        return;
+    }
+
+    let mut middleware_args = conn_args.middleware_args.clone().cache(true);
+
+    if let Some(size) = size {
+        middleware_args = middleware_args.cache_size(size);
+    }
+
+    if let Some(validity_ms) = validity_ms {
+        middleware_args = middleware_args.cache_validity_time(validity_ms);
+    }
+
+    conn_args.middleware_args = middleware_args;
+}
+
+/// Picks the process to dump among those named `process_name`, given their PIDs, by its index in
+/// `pids` or by its PID. Fails if there are several and neither is given, listing them to choose
+/// from, rather than picking one arbitrarily.
+fn select_instance(
+    process_name: &str,
+    pids: &[Pid],
+    instance: Option<usize>,
+    pid: Option<Pid>,
+) -> Result<usize> {
+    if pids.is_empty() {
+        bail!("unable to find process: {}", process_name);
+    }
+
+    if let Some(pid) = pid {
+        return pids
+            .iter()
+            .position(|&other| other == pid)
+            .with_context(|| format!("no process {} with PID {}", process_name, pid));
+    }
+
+    if let Some(instance) = instance {
+        if instance >= pids.len() {
+            bail!(
+                "instance {} is out of range, found {} processes named {}",
+                instance,
+                pids.len(),
+                process_name
+            );
+        }
+
+        return Ok(instance);
+    }
+
+    if pids.len() > 1 {
+        let list = pids
+            .iter()
+            .enumerate()
+            .map(|(i, pid)| format!("[{}] PID {}", i, pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        bail!(
+            "found {} processes named {} ({}), select one with --instance or --pid",
+            pids.len(),
+            process_name,
+            list
+        );
+    }
+
+    Ok(0)
+}
+
+fn parse_connector_args(s: &str) -> Result<ConnectorArgs, String> {
+    ConnectorArgs::from_str(s).map_err(|err| format!("invalid connector arguments: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn select_offsets_for_partial() {
+        let offsets = analysis::OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwEntityList".to_string(), 0x1000),
+                ("dwSensitivity".to_string(), 0x2000),
+            ]),
+        )]);
+
+        let selected = select_offsets(&offsets, None, &["dwSens*".to_string()]);
+
+        assert_eq!(
+            selected["client.dll"],
+            BTreeMap::from([("dwEntityList".to_string(), 0x1000)])
+        );
+
+        assert_eq!(select_offsets(&offsets, None, &[]), offsets);
+        assert_eq!(
+            select_offsets(&offsets, Some("input"), &[])["client.dll"],
+            BTreeMap::from([("dwSensitivity".to_string(), 0x2000)])
+        );
+    }
+
+    #[test]
+    fn missing_process() {
+        let args = Args::try_parse_from(["cs2-dumper", "-p", "cs2_beta.exe"]).unwrap();
+
+        let err = select_instance(&args.process_name, &[], None, None).unwrap_err();
+
+        assert_eq!(err.to_string(), "unable to find process: cs2_beta.exe");
+    }
+
+    #[test]
+    fn select_instances() {
+        let pids = [1200, 3400];
+
+        assert_eq!(select_instance("cs2.exe", &pids, Some(0), None).unwrap(), 0);
+        assert_eq!(select_instance("cs2.exe", &pids, Some(1), None).unwrap(), 1);
+        assert_eq!(
+            select_instance("cs2.exe", &pids, None, Some(3400)).unwrap(),
+            1
+        );
+
+        assert!(select_instance("cs2.exe", &pids, Some(2), None).is_err());
+        assert!(select_instance("cs2.exe", &pids, None, Some(5600)).is_err());
+
+        let err = select_instance("cs2.exe", &pids, None, None).unwrap_err();
+
+        assert!(err.to_string().contains("[0] PID 1200, [1] PID 3400"));
+
+        assert_eq!(select_instance("cs2.exe", &[1200], None, None).unwrap(), 0);
+        assert!(select_instance("cs2.exe", &[], None, None).is_err());
+
+        assert!(Args::try_parse_from(["cs2-dumper", "--instance", "1", "--pid", "3400"]).is_err());
+    }
+
+    #[test]
+    fn connector_args() {
+        let args = Args::try_parse_from([
+            "cs2-dumper",
+            "-c",
+            "qemu",
+            "-a",
+            "win10:map_base=0x1000,map_size=0x2000",
+        ])
+        .unwrap();
+
+        let conn_args = args.connector_args.unwrap();
+
+        assert_eq!(conn_args.target.unwrap().to_string(), "win10");
+        assert_eq!(conn_args.extra_args.get("map_base"), Some("0x1000"));
+        assert_eq!(conn_args.extra_args.get("map_size"), Some("0x2000"));
+
+        let args =
+            Args::try_parse_from(["cs2-dumper", "-c", "pcileech", "-a", ":device=FPGA"]).unwrap();
+
+        let conn_args = args.connector_args.unwrap();
+
+        assert!(conn_args.target.is_none());
+        assert_eq!(conn_args.extra_args.get("device"), Some("FPGA"));
+
+        assert!(Args::try_parse_from(["cs2-dumper", "-a", ":device=FPGA"]).is_err());
+    }
+
+    #[test]
+    fn cache_args() {
+        let args = Args::try_parse_from([
+            "cs2-dumper",
+            "-c",
+            "qemu",
+            "--cache-size",
+            "4194304",
+            "--cache-validity",
+            "500",
+        ])
+        .unwrap();
+
+        let mut conn_args = ConnectorArgs::default();
+
+        apply_cache_args(&mut conn_args, args.cache_size, args.cache_validity);
+
+        assert!(conn_args.middleware_args.cache);
+        assert_eq!(conn_args.middleware_args.cache_size, 0x400000);
+        assert_eq!(conn_args.middleware_args.cache_validity_time, 500);
+
+        let mut conn_args = ConnectorArgs::default();
+
+        apply_cache_args(&mut conn_args, None, None);
+
+        assert!(!conn_args.middleware_args.cache);
+
+        assert!(Args::try_parse_from(["cs2-dumper", "--cache-size", "4096"]).is_err());
+    }
+
+    #[test]
+    fn print_absolute_conflicts() {
+        assert!(
+            Args::try_parse_from(["cs2-dumper", "--print-absolute"])
+                .unwrap()
+                .print_absolute
+        );
+        assert!(Args::try_parse_from(["cs2-dumper", "--print-absolute", "--stdout"]).is_err());
+        assert!(
+            Args::try_parse_from(["cs2-dumper", "--print-absolute", "--from-disk", "bin"]).is_err()
+        );
+    }
+
+    #[test]
+    fn sample_args() {
+        let args =
+            Args::try_parse_from(["cs2-dumper", "--sample-size", "8", "--sample-seed", "42"])
+                .unwrap();
+
+        assert_eq!(args.sample_size, Some(8));
+        assert_eq!(args.sample_seed, 42);
+
+        assert!(Args::try_parse_from(["cs2-dumper", "--sample-seed", "42"]).is_err());
+        assert!(Args::try_parse_from(["cs2-dumper", "--sample-size", "8", "--stdout"]).is_err());
+    }
+
+    #[test]
+    fn force_requires_compare_build() {
+        assert!(Args::try_parse_from(["cs2-dumper", "--force"]).is_err());
+        assert!(
+            Args::try_parse_from(["cs2-dumper", "--compare-build", "build.txt", "--force"]).is_ok()
+        );
+    }
+
+    #[test]
+    fn override_pattern_repeatable() {
+        let args = Args::try_parse_from([
+            "cs2-dumper",
+            "--override-pattern",
+            "client:dwEntityList=488935${'}",
+            "--override-pattern",
+            "engine2:dwBuildNumber=8905${'}",
+        ])
+        .unwrap();
+
+        assert_eq!(args.override_pattern.len(), 2);
+    }
+
+    #[test]
+    fn list_formats_command() {
+        let args = Args::try_parse_from(["cs2-dumper", "list-formats"]).unwrap();
+
+        assert!(matches!(args.command, Some(Command::ListFormats)));
+    }
+
+    #[test]
+    fn from_disk_conflicts() {
+        for arg in [
+            "--include-abs",
+            "--compare-build=build.txt",
+            "--instance=1",
+            "--pid=1234",
+        ] {
+            assert!(
+                Args::try_parse_from(["cs2-dumper", "--from-disk", ".", arg]).is_err(),
+                "{}",
+                arg
+            );
+        }
+
+        let args = Args::try_parse_from(["cs2-dumper", "--from-disk", ".", "verify-live"]).unwrap();
+
+        assert!(check_from_disk_command(args.command.as_ref()).is_err());
+
+        let args = Args::try_parse_from([
+            "cs2-dumper",
+            "--from-disk",
+            ".",
+            "find-bytes",
+            "client.dll",
+            "48",
+        ])
+        .unwrap();
+
+        assert!(check_from_disk_command(args.command.as_ref()).is_err());
+        assert!(check_from_disk_command(None).is_ok());
+    }
+
+    #[test]
+    fn require_build() {
+        assert!(check_build_number(false, None).is_ok());
+        assert!(check_build_number(true, Some(14030)).is_ok());
+
+        let err = check_build_number(true, None).unwrap_err();
+
+        assert!(err.to_string().contains("--require-build"));
+
+        assert!(
+            Args::try_parse_from(["cs2-dumper", "--require-build", "--from-disk", "."]).is_err()
+        );
+    }
+
+    #[test]
+    fn find_bytes_command() {
+        let args =
+            Args::try_parse_from(["cs2-dumper", "find-bytes", "client.dll", "48 8b 05"]).unwrap();
+
+        assert!(matches!(
+            args.command,
+            Some(Command::FindBytes { module, bytes }) if module == "client.dll" && bytes == "48 8b 05"
+        ));
+    }
+
+    #[test]
+    fn verify_live_command() {
+        let args = Args::try_parse_from(["cs2-dumper", "-p", "cs2.exe", "verify-live"]).unwrap();
+
+        assert!(matches!(args.command, Some(Command::VerifyLive)));
+        assert!(
+            Args::try_parse_from(["cs2-dumper"])
+                .unwrap()
+                .command
+                .is_none()
+        );
+    }
+}
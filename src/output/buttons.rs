@@ -9,8 +9,8 @@ impl CodeWriter for ButtonMap {
             writeln!(fmt, "// Module: client.dll")?;
 
             fmt.block("public static class Buttons", false, |fmt| {
-                for (name, value) in self {
-                    writeln!(fmt, "public const nint {} = {:#X};", name, value)?;
+                for (name, value) in fmt.sorted(self) {
+                    writeln!(fmt, "public const nint {} = {};", name, fmt.number(value))?;
                 }
 
                 Ok(())
@@ -26,8 +26,13 @@ impl CodeWriter for ButtonMap {
             writeln!(fmt, "// Module: client.dll")?;
 
             fmt.block("namespace buttons", false, |fmt| {
-                for (name, value) in self {
-                    writeln!(fmt, "constexpr std::ptrdiff_t {} = {:#X};", name, value)?;
+                for (name, value) in fmt.sorted(self) {
+                    writeln!(
+                        fmt,
+                        "constexpr std::ptrdiff_t {} = {};",
+                        name,
+                        fmt.number(value)
+                    )?;
                 }
 
                 Ok(())
@@ -42,7 +47,7 @@ impl CodeWriter for ButtonMap {
             BTreeMap::from_iter([("client.dll", buttons)])
         };
 
-        fmt.write_str(&serde_json::to_string_pretty(&content).unwrap())
+        fmt.write_json_file(&content)
     }
 
     fn write_rs(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -52,14 +57,14 @@ impl CodeWriter for ButtonMap {
             writeln!(fmt, "// Module: client.dll")?;
 
             fmt.block("pub mod buttons", false, |fmt| {
-                for (name, value) in self {
+                for (name, value) in fmt.sorted(self) {
                     let mut name = name.clone();
 
                     if name == "use" {
                         name = format!("r#{}", name);
                     }
 
-                    writeln!(fmt, "pub const {}: usize = {:#X};", name, value)?;
+                    writeln!(fmt, "pub const {}: usize = {};", name, fmt.number(value))?;
                 }
 
                 Ok(())
@@ -1,18 +1,173 @@
+use std::collections::BTreeMap;
 use std::fmt::{self, Write};
 
+use heck::AsShoutySnakeCase;
+
+use serde::Serialize;
+
+use super::{ConstantCase, FormatOptions, HexCase, Radix, SCHEMA_VERSION, SortBy, content_hash};
+
+use crate::analysis::{DisasmMap, ModuleBases, PatternMap, Transform, TransformMap, offset_value};
+
 pub struct Formatter<'a> {
     out: &'a mut String,
-    indent_size: usize,
+    options: FormatOptions,
+    disasm: Option<&'a DisasmMap>,
+    module_bases: Option<&'a ModuleBases>,
+    transforms: Option<&'a TransformMap>,
+    patterns: Option<&'a PatternMap>,
     indent_level: usize,
+    compact: bool,
 }
 
 impl<'a> Formatter<'a> {
-    pub fn new(out: &'a mut String, indent_size: usize) -> Self {
+    pub fn new(out: &'a mut String, options: FormatOptions) -> Self {
         Self {
             out,
-            indent_size,
+            options,
+            disasm: None,
+            module_bases: None,
+            transforms: None,
+            patterns: None,
             indent_level: 0,
+            compact: false,
+        }
+    }
+
+    /// Sets the disassembly to write as comments above offsets.
+    pub fn with_disasm(mut self, disasm: &'a DisasmMap) -> Self {
+        self.disasm = Some(disasm);
+        self
+    }
+
+    /// Sets the module base addresses to annotate offsets with their absolute address.
+    pub fn with_module_bases(mut self, module_bases: &'a ModuleBases) -> Self {
+        self.module_bases = Some(module_bases);
+        self
+    }
+
+    /// Sets the transforms that were applied to offsets, to record them next to the values.
+    pub fn with_transforms(mut self, transforms: &'a TransformMap) -> Self {
+        self.transforms = Some(transforms);
+        self
+    }
+
+    /// Sets the pattern sources to write alongside the offsets.
+    pub fn with_patterns(mut self, patterns: &'a PatternMap) -> Self {
+        self.patterns = Some(patterns);
+        self
+    }
+
+    /// Returns the pattern sources, by module and offset name, if set.
+    pub fn patterns(&self) -> Option<&'a PatternMap> {
+        self.patterns
+    }
+
+    /// Returns the transform that was applied to `name` in `module_name`, if any.
+    pub fn transform(&self, module_name: &str, name: &str) -> Option<Transform> {
+        self.transforms?.get(module_name)?.get(name).copied()
+    }
+
+    /// Returns the base address of `module_name`, if known.
+    pub fn module_base(&self, module_name: &str) -> Option<u64> {
+        self.module_bases?.get(module_name).copied()
+    }
+
+    /// Returns the disassembly of the pattern that resolved `name` in `module_name`, if any. Left
+    /// out if the patterns are redacted, since the disassembly shows the bytes they match.
+    pub fn disasm(&self, module_name: &str, name: &str) -> &'a [String] {
+        if self.options.redact_patterns {
+            return &[];
+        }
+
+        self.disasm
+            .and_then(|disasm| disasm.get(module_name)?.get(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Formats an offset as a literal in the configured radix. Hex literals are zero-padded to the
+    /// configured number of digits.
+    pub fn number<T>(&self, value: T) -> String
+    where
+        T: fmt::UpperHex + fmt::LowerHex + fmt::Display,
+    {
+        match self.options.radix {
+            Radix::Hex => self.hex(value, self.options.hex_width),
+            Radix::Dec => value.to_string(),
+        }
+    }
+
+    /// Formats the value of the offset `name` of `module_name` with [`number`](Self::number). Signed
+    /// offsets with negative values are written as negative literals, such as `-0x10`.
+    pub fn offset(&self, module_name: &str, name: &str, value: u32) -> String {
+        let value = offset_value(module_name, name, value);
+
+        if value < 0 {
+            format!("-{}", self.number(value.unsigned_abs()))
+        } else {
+            self.number(value)
+        }
+    }
+
+    /// Formats a value as a hex literal with at least `digits` digits, in the configured case.
+    /// Unlike [`number`](Self::number), this ignores the radix, for values such as addresses.
+    pub fn hex<T: fmt::UpperHex + fmt::LowerHex>(&self, value: T, digits: usize) -> String {
+        // The width includes the `0x` prefix.
+        let width = digits + 2;
+
+        match self.options.hex_case {
+            HexCase::Upper => format!("{:#0width$X}", value),
+            HexCase::Lower => format!("{:#0width$x}", value),
+        }
+    }
+
+    /// Converts `name` to upper snake case, such as `DW_ENTITY_LIST`, in the configured way.
+    pub fn constant_name(&self, name: &str) -> String {
+        match self.options.constant_case {
+            ConstantCase::Acronyms => AsShoutySnakeCase(name).to_string(),
+            ConstantCase::Naive => {
+                let mut out = String::with_capacity(name.len() + 4);
+                let mut split = false;
+
+                for c in name.chars() {
+                    if c.is_uppercase() && split {
+                        out.push('_');
+                    }
+
+                    split = c.is_lowercase() || c.is_ascii_digit();
+
+                    out.extend(c.to_uppercase());
+                }
+
+                out
+            }
+        }
+    }
+
+    /// Returns the source of a pattern as written to the generated files, with its tokens separated
+    /// by single spaces if enabled, such as `48 89 35 ${'} 48 85 f6`. Whitespace is ignored by the
+    /// pattern syntax, so both forms match the same bytes.
+    pub fn pattern(&self, source: &str) -> String {
+        if self.options.redact_patterns {
+            "<redacted>".to_string()
+        } else if self.options.pretty_patterns {
+            pretty_pattern(source)
+        } else {
+            source.to_string()
+        }
+    }
+
+    /// Returns the entries of `map` in the configured output order.
+    pub fn sorted<'m, K, V: Ord>(&self, map: &'m BTreeMap<K, V>) -> Vec<(&'m K, &'m V)> {
+        let mut entries: Vec<_> = map.iter().collect();
+
+        if self.options.sort_by == SortBy::Value {
+            // Stable, so entries with the same value stay sorted by name.
+            entries.sort_by(|a, b| a.1.cmp(b.1));
         }
+
+        entries
     }
 
     // TODO: Refactor this.
@@ -29,6 +184,53 @@ impl<'a> Formatter<'a> {
         Ok(())
     }
 
+    /// Writes JSON without any whitespace for the duration of `f`.
+    pub fn compact<F>(&mut self, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Self) -> fmt::Result,
+    {
+        let compact = self.compact;
+
+        self.compact = true;
+
+        let result = f(self);
+
+        self.compact = compact;
+
+        result
+    }
+
+    pub fn write_json<T: Serialize + ?Sized>(&mut self, value: &T) -> fmt::Result {
+        let content = if self.compact {
+            serde_json::to_string(value)
+        } else {
+            serde_json::to_string_pretty(value)
+        };
+
+        self.write_str(&content.map_err(|_| fmt::Error)?)
+    }
+
+    /// Writes `value` as a JSON file, adding a top-level `schema_version` key if it's an object, and
+    /// a `content_hash` key of the rest of the object if `--content-hash` is set.
+    pub fn write_json_file<T: Serialize + ?Sized>(&mut self, value: &T) -> fmt::Result {
+        let mut value = serde_json::to_value(value).map_err(|_| fmt::Error)?;
+
+        if let Some(object) = value.as_object_mut() {
+            object.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+
+            if self.options.content_hash {
+                let content = serde_json::to_string(object).map_err(|_| fmt::Error)?;
+
+                object.insert(
+                    "content_hash".to_string(),
+                    format!("{:016x}", content_hash(&content)).into(),
+                );
+            }
+        }
+
+        self.write_json(&value)
+    }
+
     pub fn indent<F>(&mut self, f: F) -> fmt::Result
     where
         F: FnOnce(&mut Self) -> fmt::Result,
@@ -45,13 +247,75 @@ impl<'a> Formatter<'a> {
     #[inline]
     fn push_indentation(&mut self) {
         if self.indent_level > 0 {
-            let indentation = " ".repeat(self.indent_level * self.indent_size);
+            let indentation = " ".repeat(self.indent_level * self.options.indent_size);
 
             self.out.push_str(&indentation);
         }
     }
 }
 
+fn pretty_pattern(source: &str) -> String {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut token = c.to_string();
+
+        match c {
+            // A byte, such as `48`.
+            c if c.is_ascii_hexdigit() => {
+                if let Some(next) = chars.next_if(char::is_ascii_hexdigit) {
+                    token.push(next);
+                }
+            }
+            // A read of a value, such as `u4`, or an alignment, such as `@4`.
+            'u' | 'i' | '@' => {
+                if let Some(next) = chars.next_if(char::is_ascii_digit) {
+                    token.push(next);
+                }
+            }
+            // A range of bytes to skip, such as `[4-8]`, or a string, which are kept as they are.
+            '[' | '"' => {
+                let end = if c == '[' { ']' } else { '"' };
+
+                for next in chars.by_ref() {
+                    token.push(next);
+
+                    if next == end {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        tokens.push(token);
+    }
+
+    let mut out = String::with_capacity(source.len() * 3 / 2);
+
+    // Braces stick to the jump before them, such as `${`, and groups don't pad their contents.
+    let mut glue = true;
+
+    for token in tokens {
+        let closing = token == "}" || token == ")";
+
+        if !(glue || closing || token == "{") {
+            out.push(' ');
+        }
+
+        glue = token == "{" || token == "(";
+
+        out.push_str(&token);
+    }
+
+    out
+}
+
 impl<'a> Write for Formatter<'a> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         let mut lines = s.lines().peekable();
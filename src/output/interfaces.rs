@@ -15,15 +15,21 @@ impl CodeWriter for InterfaceMap {
                     &format!("public static class {}", AsPascalCase(slugify(module_name))),
                     false,
                     |fmt| {
-                        for (name, value) in ifaces {
+                        for (name, value) in fmt.sorted(ifaces) {
                             if *value > i32::MAX as u64 {
                                 writeln!(
                                     fmt,
-                                    "public static readonly nint {} = unchecked((nint){:#X});",
-                                    name, value
+                                    "public static readonly nint {} = unchecked((nint){});",
+                                    name,
+                                    fmt.number(value)
                                 )?;
                             } else {
-                                writeln!(fmt, "public const nint {} = {:#X};", name, value)?;
+                                writeln!(
+                                    fmt,
+                                    "public const nint {} = {};",
+                                    name,
+                                    fmt.number(value)
+                                )?;
                             };
                         }
 
@@ -49,8 +55,13 @@ impl CodeWriter for InterfaceMap {
                         &format!("namespace {}", AsSnakeCase(slugify(module_name))),
                         false,
                         |fmt| {
-                            for (name, value) in ifaces {
-                                writeln!(fmt, "constexpr std::ptrdiff_t {} = {:#X};", name, value)?;
+                            for (name, value) in fmt.sorted(ifaces) {
+                                writeln!(
+                                    fmt,
+                                    "constexpr std::ptrdiff_t {} = {};",
+                                    name,
+                                    fmt.number(value)
+                                )?;
                             }
 
                             Ok(())
@@ -74,7 +85,7 @@ impl CodeWriter for InterfaceMap {
             })
             .collect();
 
-        fmt.write_str(&serde_json::to_string_pretty(&content).unwrap())
+        fmt.write_json_file(&content)
     }
 
     fn write_rs(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -89,8 +100,13 @@ impl CodeWriter for InterfaceMap {
                         &format!("pub mod {}", AsSnakeCase(slugify(module_name))),
                         false,
                         |fmt| {
-                            for (name, value) in ifaces {
-                                writeln!(fmt, "pub const {}: usize = {:#X};", name, value)?;
+                            for (name, value) in fmt.sorted(ifaces) {
+                                writeln!(
+                                    fmt,
+                                    "pub const {}: usize = {};",
+                                    name,
+                                    fmt.number(value)
+                                )?;
                             }
 
                             Ok(())
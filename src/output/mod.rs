@@ -1,15 +1,24 @@
+use std::collections::BTreeMap;
 use std::fmt::{self, Write};
-use std::fs;
-use std::path::Path;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 
 use chrono::{DateTime, Utc};
 
+use clap::ValueEnum;
+
 use memflow::prelude::v1::*;
 
+use pelite::pe64::Rva;
+
 use serde_json::json;
 
+use sha2::{Digest, Sha256};
+
 use formatter::Formatter;
 
 use crate::analysis::*;
@@ -20,6 +29,143 @@ mod interfaces;
 mod offsets;
 mod schemas;
 
+/// The version of the structure of the JSON files, written as their top-level `schema_version`
+/// key. Bumped whenever the structure changes.
+pub const SCHEMA_VERSION: u32 = 5;
+
+/// The order in which entries are written to the generated code files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    #[default]
+    Name,
+    Value,
+}
+
+/// The radix of the offsets in the generated code files. JSON files always use decimal numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Radix {
+    #[default]
+    Hex,
+    Dec,
+}
+
+/// The case of the digits in hex literals, such as `0xABCD` or `0xabcd`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum HexCase {
+    #[default]
+    Upper,
+    Lower,
+}
+
+/// How names are converted to upper snake case, such as for the environment variables of `env`
+/// files. Other files keep the original names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConstantCase {
+    /// Keep runs of capitals together as a word, so `dwCSGOInput` becomes `DW_CSGO_INPUT`.
+    #[default]
+    Acronyms,
+    /// Only split before a capital that follows a lowercase letter or digit, so `dwCSGOInput`
+    /// becomes `DW_CSGOINPUT`.
+    Naive,
+}
+
+/// Options that control how the generated files are formatted.
+#[derive(Clone, Copy, Debug)]
+pub struct FormatOptions {
+    /// The number of spaces to use per indentation level.
+    pub indent_size: usize,
+    /// The order in which entries are written to the generated code files.
+    pub sort_by: SortBy,
+    /// The radix of the offsets in the generated code files.
+    pub radix: Radix,
+    /// The minimum number of digits in hex literals. `0` uses as few digits as needed.
+    pub hex_width: usize,
+    /// The case of the digits in hex literals.
+    pub hex_case: HexCase,
+    /// How names are converted to upper snake case.
+    pub constant_case: ConstantCase,
+    /// Leave out modules without any offsets.
+    pub skip_empty_modules: bool,
+    /// Write a hash of the generated content, which doesn't change with the timestamp, to the banner.
+    pub content_hash: bool,
+    /// Separate the bytes and other tokens of patterns with spaces for display.
+    pub pretty_patterns: bool,
+    /// Replace the patterns in the generated files with a placeholder and leave out files that
+    /// consist of them, such as the `mask` files.
+    pub redact_patterns: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_size: 4,
+            sort_by: SortBy::Name,
+            radix: Radix::Hex,
+            hex_width: 0,
+            hex_case: HexCase::Upper,
+            constant_case: ConstantCase::Acronyms,
+            skip_empty_modules: true,
+            content_hash: false,
+            pretty_patterns: false,
+            redact_patterns: false,
+        }
+    }
+}
+
+/// A file type that can be generated.
+#[derive(Clone, Copy, Debug)]
+pub struct Format {
+    /// The name passed to `--file-types` and `--format`.
+    pub name: &'static str,
+    /// A one-line description of the generated files.
+    pub description: &'static str,
+    /// Whether only the offsets can be written in this format.
+    pub offsets_only: bool,
+}
+
+impl Format {
+    /// The extension of the generated files.
+    pub fn extension(&self) -> &'static str {
+        extension(self.name)
+    }
+}
+
+/// All supported file types, in alphabetical order.
+#[rustfmt::skip]
+pub const FORMATS: &[Format] = &[
+    Format { name: "bat", description: "A batch file that sets an environment variable per offset", offsets_only: true },
+    Format { name: "cs", description: "C# classes", offsets_only: false },
+    Format { name: "cs_enum", description: "A single C# enum", offsets_only: true },
+    Format { name: "cs_partial", description: "A C# partial class", offsets_only: true },
+    Format { name: "cs_serializable", description: "[Serializable] C# classes with public fields for Unity", offsets_only: true },
+    Format { name: "ct", description: "A Cheat Engine table", offsets_only: true },
+    Format { name: "dbg_map", description: "Module-relative labels for debuggers, such as client.dll+0x1234 dwEntityList", offsets_only: true },
+    Format { name: "env", description: "A shell script that exports an environment variable per offset", offsets_only: true },
+    Format { name: "ex", description: "Elixir modules", offsets_only: true },
+    Format { name: "frida", description: "A JavaScript module for Frida", offsets_only: true },
+    Format { name: "hpp", description: "C++ namespaces", offsets_only: false },
+    Format { name: "hpp_bundle", description: "A single cs2_dumper.hpp with the offsets and their patterns", offsets_only: true },
+    Format { name: "java", description: "An Offsets.java class", offsets_only: true },
+    Format { name: "js", description: "A JavaScript module", offsets_only: true },
+    Format { name: "js_bigint", description: "A JavaScript module with BigInt offsets and absolute addresses", offsets_only: true },
+    Format { name: "json", description: "JSON objects", offsets_only: false },
+    Format { name: "json_array", description: "A flat JSON array of objects", offsets_only: true },
+    Format { name: "json_compact", description: "JSON objects without whitespace", offsets_only: false },
+    Format { name: "json_ids", description: "JSON offsets keyed by stable numeric IDs, with a table of their names", offsets_only: true },
+    Format { name: "mask", description: "C++ byte arrays and masks of the patterns for runtime scanners", offsets_only: true },
+    Format { name: "mjs", description: "An ES module for Node.js and Bun", offsets_only: true },
+    Format { name: "ndjson", description: "One JSON object per offset and line", offsets_only: true },
+    Format { name: "rs", description: "Rust modules", offsets_only: false },
+    Format { name: "rs_build", description: "Rust modules for include!()", offsets_only: true },
+    Format { name: "rs_phf", description: "Rust phf maps for lookups by name", offsets_only: true },
+    Format { name: "ts_enum", description: "TypeScript const enums", offsets_only: true },
+];
+
+/// Looks up a file type by name.
+pub fn format(name: &str) -> Option<&'static Format> {
+    FORMATS.iter().find(|format| format.name == name)
+}
+
 enum Item<'a> {
     Buttons(&'a ButtonMap),
     Interfaces(&'a InterfaceMap),
@@ -28,15 +174,54 @@ enum Item<'a> {
 }
 
 impl<'a> Item<'a> {
+    /// Whether the item can be written as the given file type. Formats other than the default
+    /// ones are only available for offsets.
+    fn supports(&self, file_type: &str) -> bool {
+        match format(file_type) {
+            Some(format) => !format.offsets_only || matches!(self, Item::Offsets(_)),
+            None => false,
+        }
+    }
+
     fn write(&self, fmt: &mut Formatter<'a>, file_type: &str) -> fmt::Result {
-        match file_type {
-            "cs" => self.write_cs(fmt),
-            "hpp" => self.write_hpp(fmt),
-            "json" => self.write_json(fmt),
-            "rs" => self.write_rs(fmt),
+        match (file_type, self) {
+            ("cs", _) => self.write_cs(fmt),
+            ("hpp", _) => self.write_hpp(fmt),
+            ("json", _) => self.write_json(fmt),
+            ("json_compact", _) => self.write_json_compact(fmt),
+            ("rs", _) => self.write_rs(fmt),
+            ("bat", Item::Offsets(map)) => offsets::write_env(map, true, fmt),
+            ("cs_enum", Item::Offsets(map)) => offsets::write_cs_enum(map, fmt),
+            ("cs_partial", Item::Offsets(map)) => offsets::write_cs_partial(map, fmt),
+            ("cs_serializable", Item::Offsets(map)) => offsets::write_cs_serializable(map, fmt),
+            ("ct", Item::Offsets(map)) => offsets::write_ct(map, fmt),
+            ("dbg_map", Item::Offsets(map)) => offsets::write_dbg_map(map, &expressions(map), fmt),
+            ("env", Item::Offsets(map)) => offsets::write_env(map, false, fmt),
+            ("ex", Item::Offsets(map)) => offsets::write_ex(map, fmt),
+            ("frida", Item::Offsets(map)) => offsets::write_frida(map, fmt),
+            ("hpp_bundle", Item::Offsets(map)) => {
+                offsets::write_hpp_bundle(map, fmt.patterns().unwrap_or(&PatternMap::new()), fmt)
+            }
+            ("java", Item::Offsets(map)) => offsets::write_java(map, fmt),
+            ("js", Item::Offsets(map)) => offsets::write_js(map, false, fmt),
+            ("js_bigint", Item::Offsets(map)) => offsets::write_js(map, true, fmt),
+            ("json_array", Item::Offsets(map)) => {
+                offsets::write_json_array(map, &expressions(map), fmt)
+            }
+            ("mask", Item::Offsets(_)) => offsets::write_mask_bytes(&catalog(), fmt),
+            ("json_ids", Item::Offsets(map)) => offsets::write_json_ids(map, fmt),
+            ("mjs", Item::Offsets(map)) => offsets::write_mjs(map, fmt),
+            ("ndjson", Item::Offsets(map)) => offsets::write_ndjson(map, &expressions(map), fmt),
+            ("rs_build", Item::Offsets(map)) => offsets::write_rs_build(map, fmt),
+            ("rs_phf", Item::Offsets(map)) => offsets::write_rs_phf(map, fmt),
+            ("ts_enum", Item::Offsets(map)) => offsets::write_ts_enum(map, fmt),
             _ => unimplemented!(),
         }
     }
+
+    fn write_json_compact(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        fmt.compact(|fmt| self.write_json(fmt))
+    }
 }
 
 trait CodeWriter {
@@ -86,31 +271,42 @@ impl<'a> CodeWriter for Item<'a> {
 
 pub struct Output<'a> {
     file_types: &'a [String],
-    indent_size: usize,
+    options: FormatOptions,
     out_dir: &'a Path,
     result: &'a AnalysisResult,
     timestamp: DateTime<Utc>,
+    /// The name, size and SHA-256 of every file written so far, for the manifest.
+    written: Mutex<Vec<(String, usize, String)>>,
 }
 
 impl<'a> Output<'a> {
     pub fn new(
         file_types: &'a [String],
-        indent_size: usize,
+        options: FormatOptions,
         out_dir: &'a Path,
         result: &'a AnalysisResult,
     ) -> Result<Self> {
+        if let Some(file_type) = file_types
+            .iter()
+            .find(|file_type| format(file_type).is_none())
+        {
+            bail!("unknown file type: {} (see `list-formats`)", file_type);
+        }
+
         fs::create_dir_all(&out_dir)?;
 
         Ok(Self {
             file_types,
-            indent_size,
+            options,
             out_dir,
             result,
             timestamp: Utc::now(),
+            written: Mutex::new(Vec::new()),
         })
     }
 
-    pub fn dump_all<P: MemoryView + Process>(&self, process: &mut P) -> Result<()> {
+    /// Writes all items and `info.json`, which only has a `build_number` if it's known.
+    pub fn dump_all(&self, build_number: Option<u32>) -> Result<()> {
         let items = [
             ("buttons", Item::Buttons(&self.result.buttons)),
             ("interfaces", Item::Interfaces(&self.result.interfaces)),
@@ -122,53 +318,223 @@ impl<'a> Output<'a> {
         }
 
         self.dump_schemas()?;
-        self.dump_info(process)?;
+        self.dump_info(build_number)?;
+
+        Ok(())
+    }
+
+    /// Writes everything that supports `file_type` to `out` instead of separate files, one item
+    /// after another.
+    pub fn write_all<W: io::Write>(&self, file_type: &str, out: &mut W) -> Result<()> {
+        if format(file_type).is_none() {
+            bail!("unknown file type: {} (see `list-formats`)", file_type);
+        }
+
+        let items = [
+            Item::Buttons(&self.result.buttons),
+            Item::Interfaces(&self.result.interfaces),
+            Item::Offsets(&self.result.offsets),
+            Item::Schemas(&self.result.schemas),
+        ];
+
+        for item in &items {
+            if !item.supports(file_type) {
+                continue;
+            }
+
+            let content = self.render(item, file_type)?;
+
+            out.write_all(content.as_bytes())?;
+
+            // Keep items on separate lines, the JSON writers don't end with a newline.
+            if !content.ends_with('\n') {
+                out.write_all(b"\n")?;
+            }
+        }
 
         Ok(())
     }
 
-    fn dump_info<P: MemoryView + Process>(&self, process: &mut P) -> Result<()> {
+    /// Writes only the offsets, for results that weren't obtained from a running process.
+    pub fn dump_offsets(&self) -> Result<()> {
+        self.dump_item("offsets", &Item::Offsets(&self.result.offsets))
+    }
+
+    fn dump_info(&self, build_number: Option<u32>) -> Result<()> {
         let file_path = self.out_dir.join("info.json");
 
-        let build_number = self
-            .result
-            .offsets
-            .iter()
-            .find_map(|(module_name, offsets)| {
-                let module = process.module_by_name(module_name).ok()?;
-                let offset = offsets.iter().find(|(name, _)| *name == "dwBuildNumber")?.1;
+        let mut info = json!({
+            "timestamp": self.timestamp.to_rfc3339(),
+        });
 
-                process.read::<u32>(module.base + offset).data_part().ok()
+        match build_number {
+            Some(build_number) => info["build_number"] = build_number.into(),
+            None => warn!("build number unknown, leaving it out of info.json"),
+        }
+
+        // Absolute addresses change with ASLR, so only the module bases they were computed from
+        // make them usable elsewhere.
+        if !self.result.module_bases.is_empty() {
+            info["absolute_addresses"] = json!({
+                "instance_specific": true,
+                "module_bases": self.result.module_bases,
+            });
+        }
+
+        if self.options.redact_patterns {
+            info["patterns_redacted"] = true.into();
+        }
+
+        if !self.result.failed_callbacks.is_empty() {
+            info["failed_callbacks"] = json!(self.result.failed_callbacks);
+        }
+
+        if !self.result.transforms.is_empty() {
+            let transforms: BTreeMap<_, BTreeMap<_, _>> = self
+                .result
+                .transforms
+                .iter()
+                .map(|(module_name, transforms)| {
+                    let transforms = transforms
+                        .iter()
+                        .map(|(name, transform)| (name, transform.to_string()))
+                        .collect();
+
+                    (module_name, transforms)
+                })
+                .collect();
+
+            info["transforms"] = json!(transforms);
+        }
+
+        let content = serde_json::to_string_pretty(&info)?;
+
+        self.write_file(&file_path, &content)
+    }
+
+    /// Writes `manifest.json` with the size and SHA-256 of every file written so far, so that
+    /// consumers can verify a dump. The manifest doesn't list itself.
+    pub fn write_manifest(&self) -> Result<()> {
+        let mut written = self.written.lock().unwrap().clone();
+
+        written.sort();
+
+        let files: Vec<_> = written
+            .iter()
+            .map(|(name, size, sha256)| {
+                json!({
+                    "name": name,
+                    "size": size,
+                    "sha256": sha256,
+                })
             })
-            .ok_or(anyhow!("failed to read build number"))?;
+            .collect();
 
         let content = serde_json::to_string_pretty(&json!({
             "timestamp": self.timestamp.to_rfc3339(),
-            "build_number": build_number,
+            "files": files,
         }))?;
 
-        fs::write(&file_path, &content)?;
+        fs::write(self.out_dir.join("manifest.json"), content)?;
+
+        Ok(())
+    }
+
+    fn write_file(&self, path: &Path, content: &str) -> Result<()> {
+        fs::write(path, content)?;
+
+        let name = path
+            .strip_prefix(self.out_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        let entry = (name, content.len(), sha256_hex(content.as_bytes()));
+
+        let mut written = self.written.lock().unwrap();
+
+        // Files that are written again, such as by `dump_offsets` after `dump_all`, are only
+        // listed once, with their latest content.
+        written.retain(|(other, ..)| *other != entry.0);
+        written.push(entry);
 
         Ok(())
     }
 
     fn dump_item(&self, file_name: &str, item: &Item) -> Result<()> {
         for file_type in self.file_types {
-            let mut out = String::new();
-            let mut fmt = Formatter::new(&mut out, self.indent_size);
+            if !item.supports(file_type) || self.is_redacted(file_type) {
+                continue;
+            }
+
+            // The bundle holds more than the item itself, so it isn't named after it, and Java
+            // requires the file to be named after its public class.
+            let file_path = match file_type {
+                "hpp_bundle" => self.out_dir.join("cs2_dumper.hpp"),
+                "java" => self.out_dir.join("Offsets.java"),
+                _ => self
+                    .out_dir
+                    .join(format!("{}.{}", file_name, extension(file_type))),
+            };
+
+            self.write_file(&file_path, &self.render(item, file_type)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether `file_type` is left out because it consists of the redacted patterns.
+    fn is_redacted(&self, file_type: &str) -> bool {
+        self.options.redact_patterns && file_type == "mask"
+    }
+
+    fn render(&self, item: &Item, file_type: &str) -> Result<String> {
+        if self.is_redacted(file_type) {
+            bail!(
+                "{} files consist of the patterns, which are redacted",
+                file_type
+            );
+        }
+
+        let offsets: OffsetMap;
+        let filtered;
 
-            if file_type != "json" {
-                self.write_banner(&mut fmt)?;
+        let item = match item {
+            Item::Offsets(map)
+                if self.options.skip_empty_modules || !self.result.transforms.is_empty() =>
+            {
+                offsets = apply_transforms(map, &self.result.transforms)
+                    .into_iter()
+                    .filter(|(_, offsets)| !self.options.skip_empty_modules || !offsets.is_empty())
+                    .collect();
+
+                filtered = Item::Offsets(&offsets);
+
+                &filtered
             }
+            _ => item,
+        };
+
+        let mut body = String::new();
+        let mut fmt = Formatter::new(&mut body, self.options)
+            .with_disasm(&self.result.disasm)
+            .with_module_bases(&self.result.module_bases)
+            .with_transforms(&self.result.transforms)
+            .with_patterns(&self.result.patterns);
 
-            item.write(&mut fmt, file_type)?;
+        item.write(&mut fmt, file_type)?;
 
-            let file_path = self.out_dir.join(format!("{}.{}", file_name, file_type));
+        let mut out = String::new();
 
-            fs::write(&file_path, out)?;
+        if let Some(comment) = line_comment(file_type) {
+            let hash = self.options.content_hash.then(|| content_hash(&body));
+
+            self.write_banner(&mut Formatter::new(&mut out, self.options), comment, hash)?;
         }
 
-        Ok(())
+        out.push_str(&body);
+
+        Ok(out)
     }
 
     fn dump_schemas(&self) -> Result<()> {
@@ -181,15 +547,441 @@ impl<'a> Output<'a> {
         Ok(())
     }
 
-    fn write_banner(&self, fmt: &mut Formatter<'_>) -> Result<()> {
-        writeln!(fmt, "// Generated using https://github.com/a2x/cs2-dumper")?;
-        writeln!(fmt, "// {}\n", self.timestamp)?;
+    fn write_banner(
+        &self,
+        fmt: &mut Formatter<'_>,
+        comment: &str,
+        hash: Option<u64>,
+    ) -> Result<()> {
+        writeln!(
+            fmt,
+            "{} Generated using https://github.com/a2x/cs2-dumper",
+            comment
+        )?;
+
+        if let Some(hash) = hash {
+            writeln!(fmt, "{} Content hash: {:016x}", comment, hash)?;
+        }
+        writeln!(fmt, "{} {}\n", comment, self.timestamp)?;
 
         Ok(())
     }
 }
 
+/// Appends the offsets of each module to a file as soon as they've been resolved, one JSON object
+/// per line, so that a crash later in the dump doesn't lose them. Can be shared between threads.
+pub struct PartialWriter {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl PartialWriter {
+    pub const FILE_NAME: &'static str = "offsets.partial.ndjson";
+
+    pub fn new(out_dir: &Path) -> Result<Self> {
+        fs::create_dir_all(out_dir)?;
+
+        let path = out_dir.join(Self::FILE_NAME);
+        let file = File::create(&path)?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn write_module(&self, module_name: &str, offsets: &BTreeMap<String, Rva>) -> Result<()> {
+        let mut line = serde_json::to_string(&json!({
+            "module": module_name,
+            "offsets": offsets,
+        }))?;
+
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+
+        io::Write::write_all(&mut *file, line.as_bytes())?;
+        io::Write::flush(&mut *file)?;
+
+        Ok(())
+    }
+
+    /// Removes the file once the complete results have been written.
+    pub fn finish(self) -> Result<()> {
+        drop(self.file);
+
+        fs::remove_file(&self.path)?;
+
+        Ok(())
+    }
+}
+
+fn extension(file_type: &str) -> &str {
+    match file_type {
+        "cs_enum" => "enum.cs",
+        "cs_partial" => "partial.cs",
+        "cs_serializable" => "serializable.cs",
+        "dbg_map" => "map",
+        "env" => "env.sh",
+        "frida" => "frida.js",
+        "hpp_bundle" => "hpp",
+        "js_bigint" => "bigint.js",
+        "json_array" => "array.json",
+        "json_compact" => "min.json",
+        "json_ids" => "ids.json",
+        "mask" => "mask.hpp",
+        "rs_build" => "inc.rs",
+        "rs_phf" => "phf.rs",
+        "ts_enum" => "enum.ts",
+        _ => file_type,
+    }
+}
+
+/// The line comment token of the given file type, if it supports comments.
+fn line_comment(file_type: &str) -> Option<&'static str> {
+    match file_type {
+        "ct" | "dbg_map" | "json" | "json_array" | "json_compact" | "json_ids" | "ndjson" => None,
+        "bat" => Some("REM"),
+        "env" | "ex" => Some("#"),
+        _ => Some("//"),
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// A 64-bit FNV-1a hash of `content`, which unlike the standard library's hasher is stable across
+/// builds.
+fn content_hash(content: &str) -> u64 {
+    content.bytes().fold(0xCBF29CE484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001B3)
+    })
+}
+
 #[inline]
 fn slugify(input: &str) -> String {
     input.replace(|c: char| !c.is_alphanumeric(), "_")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::{Deserializer, Value};
+
+    use super::*;
+
+    #[test]
+    fn known_formats() {
+        for name in ["cs", "hpp", "json", "rs"] {
+            let format = format(name).unwrap();
+
+            assert!(!format.offsets_only);
+            assert_eq!(format.extension(), name);
+        }
+
+        assert_eq!(format("json_compact").unwrap().extension(), "min.json");
+        assert!(format("cs_partial").unwrap().offsets_only);
+        assert!(format("txt").is_none());
+
+        assert!(FORMATS.windows(2).all(|pair| pair[0].name < pair[1].name));
+    }
+
+    #[test]
+    fn write_all_json() -> Result<()> {
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )]),
+            ..Default::default()
+        };
+
+        let out_dir = std::env::temp_dir();
+        let output = Output::new(&[], FormatOptions::default(), &out_dir, &result)?;
+
+        let mut out = Vec::new();
+
+        output.write_all("json", &mut out)?;
+
+        let values = Deserializer::from_slice(&out)
+            .into_iter::<Value>()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(values.len(), 4);
+        assert_eq!(values[2]["client.dll"]["dwEntityList"], 0x1234);
+
+        for value in &values {
+            assert_eq!(value["schema_version"], SCHEMA_VERSION);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn transformed_offsets() -> Result<()> {
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1238),
+                    ("dwViewMatrix".to_string(), 0x5678),
+                ]),
+            )]),
+            transforms: parse_transforms(&["client.dll:dwEntityList=div:8".to_string()])?,
+            ..Default::default()
+        };
+
+        let out_dir = std::env::temp_dir();
+        let output = Output::new(&[], FormatOptions::default(), &out_dir, &result)?;
+
+        let value: Value =
+            serde_json::from_str(&output.render(&Item::Offsets(&result.offsets), "json")?)?;
+
+        assert_eq!(
+            value["client.dll"]["dwEntityList"],
+            json!({"value": 0x247, "transform": "div:8"})
+        );
+        assert_eq!(value["client.dll"]["dwViewMatrix"], 0x5678);
+
+        let content = output.render(&Item::Offsets(&result.offsets), "hpp")?;
+
+        assert!(content.contains("// Transform: div:8\n"));
+        assert!(content.contains("constexpr std::ptrdiff_t dwEntityList = 0x247;\n"));
+
+        // The resolved offsets are kept, since they're still used to read from the game.
+        assert_eq!(result.offsets["client.dll"]["dwEntityList"], 0x1238);
+
+        Ok(())
+    }
+
+    #[test]
+    fn redacted_patterns() -> Result<()> {
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )]),
+            patterns: patterns(),
+            ..Default::default()
+        };
+
+        let options = FormatOptions {
+            redact_patterns: true,
+            ..Default::default()
+        };
+
+        let out_dir =
+            std::env::temp_dir().join(format!("cs2-dumper-redact-{}", std::process::id()));
+        let file_types = ["hpp_bundle".to_string(), "mask".to_string()];
+
+        let output = Output::new(&file_types, options, &out_dir, &result)?;
+
+        let content = output.render(&Item::Offsets(&result.offsets), "hpp_bundle")?;
+
+        assert!(content.contains("constexpr std::ptrdiff_t dwEntityList = 0x1234;\n"));
+        assert!(content.contains("constexpr const char* dwEntityList = \"<redacted>\";\n"));
+
+        let source = &result.patterns["client.dll"]["dwEntityList"];
+
+        assert!(!content.contains(source.as_str()));
+
+        assert!(
+            output
+                .render(&Item::Offsets(&result.offsets), "mask")
+                .is_err()
+        );
+
+        output.dump_offsets()?;
+
+        assert!(out_dir.join("cs2_dumper.hpp").exists());
+        assert!(!out_dir.join("offsets.mask.hpp").exists());
+
+        fs::remove_dir_all(&out_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn manifest() -> Result<()> {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )]),
+            ..Default::default()
+        };
+
+        let out_dir =
+            std::env::temp_dir().join(format!("cs2-dumper-manifest-{}", std::process::id()));
+        let file_types = ["json".to_string(), "hpp".to_string(), "java".to_string()];
+
+        let output = Output::new(&file_types, FormatOptions::default(), &out_dir, &result)?;
+
+        output.dump_offsets()?;
+        output.write_manifest()?;
+
+        let manifest: Value =
+            serde_json::from_str(&fs::read_to_string(out_dir.join("manifest.json"))?)?;
+        let files = manifest["files"].as_array().unwrap();
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| file["name"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(names, ["Offsets.java", "offsets.hpp", "offsets.json"]);
+
+        for file in files {
+            let content = fs::read(out_dir.join(file["name"].as_str().unwrap()))?;
+
+            assert_eq!(file["size"], content.len());
+            assert_eq!(file["sha256"], sha256_hex(&content));
+        }
+
+        fs::remove_dir_all(&out_dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_empty_modules() -> Result<()> {
+        let result = AnalysisResult::default();
+
+        let map = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            ),
+            ("empty.dll".to_string(), BTreeMap::new()),
+        ]);
+
+        let out_dir = std::env::temp_dir();
+        let output = Output::new(&[], FormatOptions::default(), &out_dir, &result)?;
+
+        let content = output.render(&Item::Offsets(&map), "hpp")?;
+
+        // Skip the banner.
+        let lines: Vec<_> = content.lines().skip(3).collect();
+
+        let expected = [
+            "#pragma once",
+            "",
+            "#include <cstddef>",
+            "",
+            "namespace cs2_dumper {",
+            "    namespace offsets {",
+            "        // Module: client.dll",
+            "        namespace client_dll {",
+            "            constexpr std::ptrdiff_t dwEntityList = 0x1234;",
+            "        }",
+            "    }",
+            "}",
+        ];
+
+        assert_eq!(lines, expected);
+
+        let options = FormatOptions {
+            skip_empty_modules: false,
+            ..Default::default()
+        };
+
+        let output = Output::new(&[], options, &out_dir, &result)?;
+
+        assert!(
+            output
+                .render(&Item::Offsets(&map), "hpp")?
+                .contains("empty_dll")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn stable_content_hash() -> Result<()> {
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )]),
+            ..Default::default()
+        };
+
+        let options = FormatOptions {
+            content_hash: true,
+            ..Default::default()
+        };
+
+        let out_dir = std::env::temp_dir();
+
+        let mut first = Output::new(&[], options, &out_dir, &result)?;
+        let mut second = Output::new(&[], options, &out_dir, &result)?;
+
+        first.timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        second.timestamp = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+
+        let item = Item::Offsets(&result.offsets);
+
+        let hash_line = |content: &str| {
+            content
+                .lines()
+                .find(|line| line.starts_with("// Content hash: "))
+                .map(str::to_string)
+        };
+
+        let first = first.render(&item, "hpp")?;
+        let second = second.render(&item, "hpp")?;
+
+        assert_ne!(first, second);
+        assert!(hash_line(&first).is_some());
+        assert_eq!(hash_line(&first), hash_line(&second));
+
+        Ok(())
+    }
+
+    #[test]
+    fn json_content_hash() -> Result<()> {
+        let result = AnalysisResult {
+            offsets: OffsetMap::from([(
+                "client.dll".to_string(),
+                BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+            )]),
+            ..Default::default()
+        };
+
+        let options = FormatOptions {
+            content_hash: true,
+            ..Default::default()
+        };
+
+        let out_dir = std::env::temp_dir();
+
+        let mut first = Output::new(&[], options, &out_dir, &result)?;
+        let mut second = Output::new(&[], options, &out_dir, &result)?;
+
+        first.timestamp = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        second.timestamp = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+
+        let item = Item::Offsets(&result.offsets);
+
+        let first: serde_json::Value = serde_json::from_str(&first.render(&item, "json")?)?;
+        let second: serde_json::Value = serde_json::from_str(&second.render(&item, "json")?)?;
+
+        assert!(first["content_hash"].is_string());
+        assert_eq!(first["content_hash"], second["content_hash"]);
+
+        let without_hash = Output::new(&[], FormatOptions::default(), &out_dir, &result)?;
+        let without_hash: serde_json::Value =
+            serde_json::from_str(&without_hash.render(&item, "json")?)?;
+
+        assert!(without_hash.get("content_hash").is_none());
+
+        Ok(())
+    }
+}
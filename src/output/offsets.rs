@@ -1,8 +1,18 @@
+use std::collections::BTreeMap;
 use std::fmt::{self, Write};
 
 use heck::{AsPascalCase, AsSnakeCase};
 
-use super::{CodeWriter, Formatter, OffsetMap, slugify};
+use serde_json::json;
+
+use super::{CodeWriter, Formatter, OffsetMap, content_hash, slugify};
+
+use pelite::pattern::Atom;
+
+use crate::analysis::{
+    Confidence, ExpressionMap, PatternAttrs, PatternMap, confidence, expressions, is_address,
+    is_signed, mask_bytes, offset_value, pattern_attrs, target,
+};
 
 impl CodeWriter for OffsetMap {
     fn write_cs(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -14,8 +24,19 @@ impl CodeWriter for OffsetMap {
                     &format!("public static class {}", AsPascalCase(slugify(module_name))),
                     false,
                     |fmt| {
-                        for (name, value) in offsets {
-                            writeln!(fmt, "public const nint {} = {:#X};", name, value)?;
+                        for (name, value) in fmt.sorted(offsets) {
+                            write_offset_comments(fmt, module_name, name)?;
+
+                            if is_deprecated(module_name, name) {
+                                writeln!(fmt, "[Obsolete]")?;
+                            }
+
+                            writeln!(
+                                fmt,
+                                "public const nint {} = {};",
+                                slugify(name),
+                                fmt.offset(module_name, name, *value)
+                            )?;
                         }
 
                         Ok(())
@@ -32,30 +53,69 @@ impl CodeWriter for OffsetMap {
         writeln!(fmt, "#include <cstddef>\n")?;
 
         fmt.block("namespace cs2_dumper", false, |fmt| {
-            fmt.block("namespace offsets", false, |fmt| {
-                for (module_name, offsets) in self {
-                    writeln!(fmt, "// Module: {}", module_name)?;
+            write_hpp_offsets(self, fmt)
+        })
+    }
 
-                    fmt.block(
-                        &format!("namespace {}", AsSnakeCase(slugify(module_name))),
-                        false,
-                        |fmt| {
-                            for (name, value) in offsets {
-                                writeln!(fmt, "constexpr std::ptrdiff_t {} = {:#X};", name, value)?;
+    fn write_json(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let content: BTreeMap<_, _> = self
+            .iter()
+            .map(|(module_name, offsets)| {
+                let offsets: BTreeMap<_, _> = offsets
+                    .iter()
+                    .map(|(name, value)| {
+                        let attrs =
+                            pattern_attrs(module_name, name).unwrap_or(PatternAttrs::DEFAULT);
+
+                        let transform = fmt.transform(module_name, name);
+                        let target = target(module_name, name);
+
+                        // Only low confidence is written, since most offsets have a unique match.
+                        let low_confidence = confidence(module_name, name) == Some(Confidence::Low);
+
+                        let value = if attrs.deprecated
+                            || target.is_some()
+                            || !attrs.layout.is_empty()
+                            || transform.is_some()
+                            || low_confidence
+                        {
+                            let mut value =
+                                json!({ "value": offset_value(module_name, name, *value) });
+
+                            if attrs.deprecated {
+                                value["deprecated"] = true.into();
                             }
 
-                            Ok(())
-                        },
-                    )?;
-                }
+                            if let Some(target) = target {
+                                value["target"] = target.into();
+                            }
 
-                Ok(())
+                            if !attrs.layout.is_empty() {
+                                value["layout"] = attrs.layout.into();
+                            }
+
+                            if let Some(transform) = transform {
+                                value["transform"] = transform.to_string().into();
+                            }
+
+                            if low_confidence {
+                                value["confidence"] = Confidence::Low.to_string().into();
+                            }
+
+                            value
+                        } else {
+                            json!(offset_value(module_name, name, *value))
+                        };
+
+                        (name, value)
+                    })
+                    .collect();
+
+                (module_name, offsets)
             })
-        })
-    }
+            .collect();
 
-    fn write_json(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        fmt.write_str(&serde_json::to_string_pretty(self).unwrap())
+        fmt.write_json_file(&content)
     }
 
     fn write_rs(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -70,8 +130,20 @@ impl CodeWriter for OffsetMap {
                         &format!("pub mod {}", AsSnakeCase(slugify(module_name))),
                         false,
                         |fmt| {
-                            for (name, value) in offsets {
-                                writeln!(fmt, "pub const {}: usize = {:#X};", name, value)?;
+                            for (name, value) in fmt.sorted(offsets) {
+                                write_offset_comments(fmt, module_name, name)?;
+
+                                if is_deprecated(module_name, name) {
+                                    writeln!(fmt, "#[deprecated]")?;
+                                }
+
+                                writeln!(
+                                    fmt,
+                                    "pub const {}: {} = {};",
+                                    slugify(name),
+                                    rust_type(module_name, name),
+                                    fmt.offset(module_name, name, *value)
+                                )?;
                             }
 
                             Ok(())
@@ -84,3 +156,1750 @@ impl CodeWriter for OffsetMap {
         })
     }
 }
+
+/// Writes the `namespace offsets` part of the C++ header, shared by the standalone and bundled
+/// headers.
+fn write_hpp_offsets(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    let expressions = expressions(offsets);
+
+    fmt.block("namespace offsets", false, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "// Module: {}", module_name)?;
+
+            if let Some(base) = fmt.module_base(module_name) {
+                writeln!(
+                    fmt,
+                    "// Image base: {} (absolute addresses are only valid for the dumped process)",
+                    fmt.hex(base, 0)
+                )?;
+            }
+
+            fmt.block(
+                &format!("namespace {}", AsSnakeCase(slugify(module_name))),
+                false,
+                |fmt| {
+                    for (name, value) in fmt.sorted(offsets) {
+                        write_offset_comments(fmt, module_name, name)?;
+
+                        let attr = if is_deprecated(module_name, name) {
+                            "[[deprecated]] "
+                        } else {
+                            ""
+                        };
+
+                        // Constants and fields aren't relative to the module base.
+                        let abs = match fmt.module_base(module_name) {
+                            Some(base)
+                                if expression(&expressions, module_name, name)
+                                    .is_none_or(is_address) =>
+                            {
+                                format!(" // abs: {}", fmt.hex(base + *value as u64, 0))
+                            }
+                            _ => String::new(),
+                        };
+
+                        writeln!(
+                            fmt,
+                            "{}constexpr std::ptrdiff_t {} = {};{}",
+                            attr,
+                            slugify(name),
+                            fmt.offset(module_name, name, *value),
+                            abs
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Writes the pattern sources as C++ string constants in a `namespace patterns` block.
+fn write_hpp_patterns(patterns: &PatternMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.block("namespace patterns", false, |fmt| {
+        for (module_name, patterns) in patterns {
+            writeln!(fmt, "// Module: {}", module_name)?;
+
+            fmt.block(
+                &format!("namespace {}", AsSnakeCase(slugify(module_name))),
+                false,
+                |fmt| {
+                    for (name, source) in patterns {
+                        writeln!(
+                            fmt,
+                            "constexpr const char* {} = {:?};",
+                            name,
+                            fmt.pattern(source)
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Writes a single C++ header with both the offsets and the patterns that resolve them.
+pub fn write_hpp_bundle(
+    offsets: &OffsetMap,
+    patterns: &PatternMap,
+    fmt: &mut Formatter<'_>,
+) -> fmt::Result {
+    writeln!(fmt, "#pragma once\n")?;
+    writeln!(fmt, "#include <cstddef>\n")?;
+
+    fmt.block("namespace cs2_dumper", false, |fmt| {
+        write_hpp_offsets(offsets, fmt)?;
+
+        writeln!(fmt)?;
+
+        write_hpp_patterns(patterns, fmt)
+    })
+}
+
+/// Writes every pattern as a C++ byte array and `x`/`?` mask for scanners without a pattern parser.
+/// Takes the entries of [`catalog`](crate::analysis::catalog), and leaves out exports.
+pub fn write_mask_bytes(entries: &[(&str, &str, &[Atom])], fmt: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(fmt, "#pragma once\n")?;
+
+    fmt.block("namespace cs2_dumper::masks", false, |fmt| {
+        let mut module_names: Vec<_> = entries
+            .iter()
+            .map(|(module_name, ..)| *module_name)
+            .collect();
+
+        module_names.dedup();
+
+        for module_name in module_names {
+            writeln!(fmt, "// Module: {}", module_name)?;
+
+            fmt.block(
+                &format!("namespace {}", AsSnakeCase(slugify(module_name))),
+                false,
+                |fmt| {
+                    let patterns = entries
+                        .iter()
+                        .filter(|(other, _, pat)| *other == module_name && !pat.is_empty());
+
+                    for (_, name, pat) in patterns {
+                        let (bytes, mask) = mask_bytes(pat);
+
+                        let bytes: Vec<_> = bytes.iter().map(|byte| fmt.hex(byte, 2)).collect();
+
+                        writeln!(
+                            fmt,
+                            "constexpr unsigned char {}_bytes[] = {{ {} }};",
+                            name,
+                            bytes.join(", ")
+                        )?;
+                        writeln!(fmt, "constexpr const char* {}_mask = {:?};", name, mask)?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Writes all modules as a single C# enum, with member names prefixed by their module name.
+pub fn write_cs_enum(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    // A `ulong` enum can't have negative members.
+    let signed = offsets
+        .iter()
+        .any(|(module_name, offsets)| offsets.keys().any(|name| is_signed(module_name, name)));
+
+    let header = if signed {
+        "public enum Offsets : long"
+    } else {
+        "public enum Offsets : ulong"
+    };
+
+    fmt.block("namespace CS2Dumper", false, |fmt| {
+        fmt.block(header, false, |fmt| {
+            for (module_name, offsets) in offsets {
+                writeln!(fmt, "// Module: {}", module_name)?;
+
+                for (name, value) in fmt.sorted(offsets) {
+                    if is_deprecated(module_name, name) {
+                        writeln!(fmt, "[Obsolete]")?;
+                    }
+
+                    writeln!(
+                        fmt,
+                        "{}_{} = {},",
+                        slugify(module_name),
+                        slugify(name),
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// Writes all modules as members of a single `partial` C# class, so that consumers can extend it
+/// in their own files. Member names are prefixed by their module name.
+pub fn write_cs_partial(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.block("namespace CS2Dumper", false, |fmt| {
+        fmt.block("public static partial class Offsets", false, |fmt| {
+            for (module_name, offsets) in offsets {
+                writeln!(fmt, "// Module: {}", module_name)?;
+
+                for (name, value) in fmt.sorted(offsets) {
+                    if is_deprecated(module_name, name) {
+                        writeln!(fmt, "[Obsolete]")?;
+                    }
+
+                    writeln!(
+                        fmt,
+                        "public const nint {}_{} = {};",
+                        slugify(module_name),
+                        slugify(name),
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// Writes a `[Serializable]` C# class per module with a public `long` field per offset, and an
+/// `Offsets` class with a field per module, so that Unity can serialize the offsets, such as in a
+/// `ScriptableObject`, and show them in the inspector. Unlike constants, the fields can be edited.
+pub fn write_cs_serializable(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(fmt, "using System;\n")?;
+
+    fmt.block("namespace CS2Dumper", false, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "// Module: {}", module_name)?;
+            writeln!(fmt, "[Serializable]")?;
+
+            fmt.block(
+                &format!("public class {}", AsPascalCase(slugify(module_name))),
+                false,
+                |fmt| {
+                    for (name, value) in fmt.sorted(offsets) {
+                        if is_deprecated(module_name, name) {
+                            writeln!(fmt, "[Obsolete]")?;
+                        }
+
+                        writeln!(
+                            fmt,
+                            "public long {} = {};",
+                            slugify(name),
+                            fmt.offset(module_name, name, *value)
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        writeln!(fmt, "[Serializable]")?;
+
+        fmt.block("public class Offsets", false, |fmt| {
+            for module_name in offsets.keys() {
+                let class_name = AsPascalCase(slugify(module_name)).to_string();
+
+                writeln!(
+                    fmt,
+                    "public {} {} = new {}();",
+                    class_name,
+                    slugify(module_name),
+                    class_name
+                )?;
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// Writes a Cheat Engine table with one group per module, holding a memory record for each offset
+/// addressed relative to the module base.
+pub fn write_ct(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(fmt, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(fmt, "<CheatTable CheatEngineTableVersion=\"45\">")?;
+
+    let mut id = 0;
+
+    let mut next_id = || {
+        id += 1;
+        id - 1
+    };
+
+    fmt.indent(|fmt| {
+        writeln!(fmt, "<CheatEntries>")?;
+
+        fmt.indent(|fmt| {
+            for (module_name, offsets) in offsets {
+                writeln!(fmt, "<CheatEntry>")?;
+
+                fmt.indent(|fmt| {
+                    writeln!(fmt, "<ID>{}</ID>", next_id())?;
+                    writeln!(fmt, "<Description>\"{}\"</Description>", module_name)?;
+                    writeln!(fmt, "<GroupHeader>1</GroupHeader>")?;
+                    writeln!(fmt, "<CheatEntries>")?;
+
+                    fmt.indent(|fmt| {
+                        for (name, value) in fmt.sorted(offsets) {
+                            writeln!(fmt, "<CheatEntry>")?;
+
+                            // Cheat Engine reads the offset as hex, so it's written without the
+                            // radix and prefix of the other files.
+                            let value = offset_value(module_name, name, *value);
+                            let sign = if value < 0 { '-' } else { '+' };
+
+                            fmt.indent(|fmt| {
+                                writeln!(fmt, "<ID>{}</ID>", next_id())?;
+                                writeln!(fmt, "<Description>\"{}\"</Description>", name)?;
+                                writeln!(fmt, "<ShowAsHex>1</ShowAsHex>")?;
+                                writeln!(fmt, "<VariableType>8 Bytes</VariableType>")?;
+                                writeln!(
+                                    fmt,
+                                    "<Address>{}{}{:X}</Address>",
+                                    module_name,
+                                    sign,
+                                    value.unsigned_abs()
+                                )
+                            })?;
+
+                            writeln!(fmt, "</CheatEntry>")?;
+                        }
+
+                        Ok(())
+                    })?;
+
+                    writeln!(fmt, "</CheatEntries>")
+                })?;
+
+                writeln!(fmt, "</CheatEntry>")?;
+            }
+
+            Ok(())
+        })?;
+
+        writeln!(fmt, "</CheatEntries>")
+    })?;
+
+    writeln!(fmt, "</CheatTable>")
+}
+
+/// Writes the Rust module of each module without the outer `cs2_dumper::offsets` modules or inner
+/// attributes, so the file can be used with `include!()`.
+pub fn write_rs_build(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    for (module_name, offsets) in offsets {
+        writeln!(fmt, "// Module: {}", module_name)?;
+        writeln!(fmt, "#[allow(dead_code, non_upper_case_globals)]")?;
+
+        fmt.block(
+            &format!("pub mod {}", AsSnakeCase(slugify(module_name))),
+            false,
+            |fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    if is_deprecated(module_name, name) {
+                        writeln!(fmt, "#[deprecated]")?;
+                    }
+
+                    writeln!(
+                        fmt,
+                        "pub const {}: {} = {};",
+                        slugify(name),
+                        rust_type(module_name, name),
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes a Java class with a nested class of `long` constants per module.
+pub fn write_java(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.block("public final class Offsets", false, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "// Module: {}", module_name)?;
+
+            fmt.block(
+                &format!(
+                    "public static final class {}",
+                    java_identifier(&slugify(module_name))
+                ),
+                false,
+                |fmt| {
+                    for (name, value) in fmt.sorted(offsets) {
+                        if is_deprecated(module_name, name) {
+                            writeln!(fmt, "@Deprecated")?;
+                        }
+
+                        writeln!(
+                            fmt,
+                            "public static final long {} = {}L;",
+                            java_identifier(name),
+                            fmt.offset(module_name, name, *value)
+                        )?;
+                    }
+
+                    Ok(())
+                },
+            )?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Makes `name` a valid Java identifier by prefixing names that start with a digit and suffixing
+/// keywords with an underscore.
+fn java_identifier(name: &str) -> String {
+    #[rustfmt::skip]
+    const KEYWORDS: &[&str] = &[
+        "abstract", "assert", "boolean", "break", "byte", "case", "catch", "char", "class", "const",
+        "continue", "default", "do", "double", "else", "enum", "extends", "false", "final",
+        "finally", "float", "for", "goto", "if", "implements", "import", "instanceof", "int",
+        "interface", "long", "native", "new", "null", "package", "private", "protected", "public",
+        "return", "short", "static", "strictfp", "super", "switch", "synchronized", "this",
+        "throw", "throws", "transient", "true", "try", "void", "volatile", "while",
+    ];
+
+    let name = slugify(name);
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// Writes a JavaScript module for Frida with the offsets and a helper that resolves an offset to an
+/// address in the game. Names are sanitized, since they're written as unquoted keys.
+pub fn write_frida(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.block("export const offsets =", true, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "'{}': {{", module_name)?;
+
+            fmt.indent(|fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    writeln!(
+                        fmt,
+                        "{}: {},",
+                        slugify(name),
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+
+                Ok(())
+            })?;
+
+            writeln!(fmt, "}},")?;
+        }
+
+        Ok(())
+    })?;
+
+    writeln!(fmt)?;
+
+    fmt.block("export function resolve(moduleName, name)", false, |fmt| {
+        writeln!(
+            fmt,
+            "return Process.getModuleByName(moduleName).base.add(offsets[moduleName][name]);"
+        )
+    })
+}
+
+/// Writes a JavaScript module with the offsets. With `bigint`, values are written as `BigInt`
+/// literals such as `0x1234n`, and the absolute address of each offset is included if the module
+/// bases are known, since those can exceed the range in which numbers are exact.
+pub fn write_js(offsets: &OffsetMap, bigint: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
+    let suffix = if bigint { "n" } else { "" };
+
+    fmt.block("export const offsets =", true, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "'{}': {{", module_name)?;
+
+            fmt.indent(|fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    writeln!(
+                        fmt,
+                        "{}: {}{},",
+                        slugify(name),
+                        fmt.offset(module_name, name, *value),
+                        suffix
+                    )?;
+                }
+
+                Ok(())
+            })?;
+
+            writeln!(fmt, "}},")?;
+        }
+
+        Ok(())
+    })?;
+
+    if !bigint
+        || offsets
+            .keys()
+            .all(|module_name| fmt.module_base(module_name).is_none())
+    {
+        return Ok(());
+    }
+
+    writeln!(fmt)?;
+
+    writeln!(
+        fmt,
+        "// Only valid for the dumped process, rebase with moduleBases."
+    )?;
+
+    fmt.block("export const moduleBases =", true, |fmt| {
+        for module_name in offsets.keys() {
+            if let Some(base) = fmt.module_base(module_name) {
+                writeln!(fmt, "'{}': {}n,", module_name, fmt.number(base))?;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    writeln!(fmt)?;
+
+    let expressions = expressions(offsets);
+
+    // Constants and fields aren't relative to the module base, so they have no address.
+    fmt.block("export const addresses =", true, |fmt| {
+        for (module_name, offsets) in offsets {
+            let Some(base) = fmt.module_base(module_name) else {
+                continue;
+            };
+
+            writeln!(fmt, "'{}': {{", module_name)?;
+
+            fmt.indent(|fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    if !expression(&expressions, module_name, name).is_none_or(is_address) {
+                        continue;
+                    }
+
+                    writeln!(
+                        fmt,
+                        "{}: {}n,",
+                        slugify(name),
+                        fmt.number(base + *value as u64)
+                    )?;
+                }
+
+                Ok(())
+            })?;
+
+            writeln!(fmt, "}},")?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Writes an ES module for Node.js and Bun with the offsets as a named and the default export. Names
+/// are written as string keys, so they don't need to be valid identifiers.
+pub fn write_mjs(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.block("export const offsets =", true, |fmt| {
+        for (module_name, offsets) in offsets {
+            writeln!(fmt, "'{}': {{", module_name)?;
+
+            fmt.indent(|fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    writeln!(
+                        fmt,
+                        "'{}': {},",
+                        name,
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+
+                Ok(())
+            })?;
+
+            writeln!(fmt, "}},")?;
+        }
+
+        Ok(())
+    })?;
+
+    writeln!(fmt)?;
+    writeln!(fmt, "export default offsets;")
+}
+
+/// Writes one environment variable per offset, such as `CLIENT_DLL_DW_ENTITY_LIST`, as `export`
+/// lines for POSIX shells or as `set` lines for batch files.
+pub fn write_env(offsets: &OffsetMap, batch: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
+    let command = if batch { "set" } else { "export" };
+
+    for (module_name, offsets) in offsets {
+        for (name, value) in fmt.sorted(offsets) {
+            writeln!(
+                fmt,
+                "{} {}_{}={}",
+                command,
+                fmt.constant_name(&slugify(module_name)),
+                fmt.constant_name(&slugify(name)),
+                fmt.offset(module_name, name, *value)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a static `phf::Map` per module from offset names to values, such as `CLIENT_DLL`, for
+/// lookups by name at runtime. The maps are generated with `phf_codegen`, so consumers only need
+/// the `phf` crate.
+pub fn write_rs_phf(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    for (i, (module_name, offsets)) in offsets.iter().enumerate() {
+        if i > 0 {
+            writeln!(fmt)?;
+        }
+
+        let mut map = phf_codegen::Map::new();
+
+        for (name, value) in offsets {
+            map.entry(name.as_str(), &fmt.offset(module_name, name, *value));
+        }
+
+        // Negative values need a signed type, which still holds every unsigned one.
+        let value_type = if offsets.keys().any(|name| is_signed(module_name, name)) {
+            "i64"
+        } else {
+            "u32"
+        };
+
+        writeln!(fmt, "// Module: {}", module_name)?;
+
+        writeln!(
+            fmt,
+            "pub static {}: phf::Map<&'static str, {}> = {};",
+            fmt.constant_name(&slugify(module_name)),
+            value_type,
+            map.build()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes a TypeScript `const enum` per module, which is inlined at compile time.
+pub fn write_ts_enum(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    for (i, (module_name, offsets)) in offsets.iter().enumerate() {
+        if i > 0 {
+            writeln!(fmt)?;
+        }
+
+        writeln!(fmt, "// Module: {}", module_name)?;
+
+        fmt.block(
+            &format!("export const enum {}", AsPascalCase(slugify(module_name))),
+            false,
+            |fmt| {
+                for (name, value) in fmt.sorted(offsets) {
+                    if is_deprecated(module_name, name) {
+                        writeln!(fmt, "/** @deprecated */")?;
+                    }
+
+                    writeln!(
+                        fmt,
+                        "{} = {},",
+                        ts_identifier(name),
+                        fmt.offset(module_name, name, *value)
+                    )?;
+                }
+
+                Ok(())
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Makes `name` a valid enum member name by replacing illegal characters and prefixing names that
+/// start with a digit, which TypeScript would parse as numeric member names.
+fn ts_identifier(name: &str) -> String {
+    let name = slugify(name);
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// Writes all offsets as a flat JSON array of objects, ordered by module and then by name.
+pub fn write_json_array(
+    offsets: &OffsetMap,
+    expressions: &ExpressionMap,
+    fmt: &mut Formatter<'_>,
+) -> fmt::Result {
+    let content: Vec<_> = offsets
+        .iter()
+        .flat_map(|(module_name, offsets)| {
+            offsets.iter().map(move |(name, value)| {
+                json!({
+                    "module": module_name,
+                    "name": name,
+                    "rva": offset_value(module_name, name, *value),
+                    "expression": expression(expressions, module_name, name),
+                })
+            })
+        })
+        .collect();
+
+    fmt.write_json(&content)
+}
+
+/// Returns a 32-bit ID of an offset that only depends on its module and name, so that it stays the
+/// same across dumps.
+pub fn offset_id(module_name: &str, name: &str) -> u32 {
+    let hash = content_hash(&format!("{}::{}", module_name, name));
+
+    (hash ^ (hash >> 32)) as u32
+}
+
+/// Writes the offsets keyed by their [`offset_id`], with a table that maps each ID back to
+/// `module::name`. Fails if two offsets have the same ID, which would make lookups ambiguous.
+pub fn write_json_ids(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    let mut names = BTreeMap::new();
+    let mut values = BTreeMap::new();
+
+    for (module_name, offsets) in offsets {
+        for (name, value) in offsets {
+            let id = offset_id(module_name, name);
+
+            if names
+                .insert(id, format!("{}::{}", module_name, name))
+                .is_some()
+            {
+                return Err(fmt::Error);
+            }
+
+            values.insert(id, offset_value(module_name, name, *value));
+        }
+    }
+
+    fmt.write_json_file(&json!({
+        "names": names,
+        "offsets": values,
+    }))
+}
+
+/// Writes a label per offset as `client.dll+0x1234 dwEntityList`, to load as symbols in debuggers
+/// such as x64dbg and WinDbg. Offsets that aren't addresses in their module, such as fields and
+/// constants, are left out, which is told apart by how they're calculated.
+pub fn write_dbg_map(
+    offsets: &OffsetMap,
+    expressions: &ExpressionMap,
+    fmt: &mut Formatter<'_>,
+) -> fmt::Result {
+    for (module_name, offsets) in offsets {
+        for (name, value) in fmt.sorted(offsets) {
+            // Addresses are relative to the module base or to another address, such as
+            // `dwPrediction + 0x180`, while fields are relative to a dereferenced offset.
+            let is_address = expression(expressions, module_name, name).is_none_or(is_address);
+
+            if is_address {
+                writeln!(fmt, "{}+{} {}", module_name, fmt.hex(value, 0), name)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes one JSON object per offset and line.
+pub fn write_ndjson(
+    offsets: &OffsetMap,
+    expressions: &ExpressionMap,
+    fmt: &mut Formatter<'_>,
+) -> fmt::Result {
+    for (module_name, offsets) in offsets {
+        for (name, value) in fmt.sorted(offsets) {
+            let record = json!({
+                "module": module_name,
+                "name": name,
+                "rva": offset_value(module_name, name, *value),
+                "expression": expression(expressions, module_name, name),
+            });
+
+            writeln!(fmt, "{}", record)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes all modules as an Elixir map keyed by module name, returned from
+/// `CS2Dumper.Offsets.offsets/0`.
+pub fn write_ex(offsets: &OffsetMap, fmt: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(fmt, "defmodule CS2Dumper.Offsets do")?;
+
+    fmt.indent(|fmt| {
+        writeln!(fmt, "def offsets do")?;
+
+        fmt.indent(|fmt| {
+            writeln!(fmt, "%{{")?;
+
+            fmt.indent(|fmt| {
+                for (i, (module_name, module_offsets)) in offsets.iter().enumerate() {
+                    writeln!(fmt, "\"{}\" => %{{", module_name)?;
+
+                    fmt.indent(|fmt| {
+                        let entries = module_offsets
+                            .iter()
+                            .map(|(name, value)| {
+                                format!("\"{}\" => {}", name, fmt.offset(module_name, name, *value))
+                            })
+                            .collect::<Vec<_>>()
+                            .join(",\n");
+
+                        writeln!(fmt, "{}", entries)
+                    })?;
+
+                    let separator = if i + 1 < offsets.len() { "," } else { "" };
+
+                    writeln!(fmt, "}}{}", separator)?;
+                }
+
+                Ok(())
+            })?;
+
+            writeln!(fmt, "}}")
+        })?;
+
+        writeln!(fmt, "end")
+    })?;
+
+    writeln!(fmt, "end")
+}
+
+fn is_deprecated(module_name: &str, name: &str) -> bool {
+    pattern_attrs(module_name, name).is_some_and(|attrs| attrs.deprecated)
+}
+
+/// Returns the Rust type of an offset, which is `isize` for signed offsets.
+fn rust_type(module_name: &str, name: &str) -> &'static str {
+    if is_signed(module_name, name) {
+        "isize"
+    } else {
+        "usize"
+    }
+}
+
+/// Writes the target, the layout, the transform and the disassembly of an offset as line comments,
+/// if it has any.
+fn write_offset_comments(fmt: &mut Formatter<'_>, module_name: &str, name: &str) -> fmt::Result {
+    let attrs = pattern_attrs(module_name, name).unwrap_or(PatternAttrs::DEFAULT);
+
+    if let Some(target) = target(module_name, name) {
+        writeln!(fmt, "// Target: {}", target)?;
+    }
+
+    if !attrs.layout.is_empty() {
+        writeln!(fmt, "// Layout: {}", attrs.layout)?;
+    }
+
+    if let Some(transform) = fmt.transform(module_name, name) {
+        writeln!(fmt, "// Transform: {}", transform)?;
+    }
+
+    for line in fmt.disasm(module_name, name) {
+        writeln!(fmt, "// {}", line)?;
+    }
+
+    Ok(())
+}
+
+fn expression<'a>(
+    expressions: &'a ExpressionMap,
+    module_name: &str,
+    name: &str,
+) -> Option<&'a str> {
+    expressions.get(module_name)?.get(name).map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::analysis::{ModuleBases, catalog};
+    use crate::output::{ConstantCase, FormatOptions, HexCase, Radix, SortBy};
+
+    fn fixture_expressions() -> ExpressionMap {
+        ExpressionMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                (
+                    "dwEntityList".to_string(),
+                    "client.dll + 0x1234".to_string(),
+                ),
+                (
+                    "dwViewMatrix".to_string(),
+                    "client.dll + 0x5678".to_string(),
+                ),
+            ]),
+        )])
+    }
+
+    fn fixture() -> OffsetMap {
+        OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1234),
+                    ("dwViewMatrix".to_string(), 0x5678),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([("dwBuildNumber".to_string(), 0x9ABC)]),
+            ),
+        ])
+    }
+
+    fn render<F>(f: F) -> String
+    where
+        F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+    {
+        render_with(FormatOptions::default(), f)
+    }
+
+    fn render_with<F>(options: FormatOptions, f: F) -> String
+    where
+        F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+    {
+        let mut out = String::new();
+
+        f(&mut Formatter::new(&mut out, options)).unwrap();
+
+        out
+    }
+
+    #[test]
+    fn cs_enum() {
+        let out = render(|fmt| write_cs_enum(&fixture(), fmt));
+
+        assert_eq!(
+            out,
+            "namespace CS2Dumper {\n    \
+             public enum Offsets : ulong {\n        \
+             // Module: client.dll\n        \
+             client_dll_dwEntityList = 0x1234,\n        \
+             client_dll_dwViewMatrix = 0x5678,\n        \
+             // Module: engine2.dll\n        \
+             engine2_dll_dwBuildNumber = 0x9ABC,\n    \
+             }\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn hpp_abs() {
+        let mut offsets = fixture();
+
+        offsets.get_mut("client.dll").unwrap().extend([
+            ("dwEntityList_chunkSize".to_string(), 0x200),
+            ("dwEntityList_field".to_string(), 0x40),
+        ]);
+
+        let bases = ModuleBases::from([("client.dll".to_string(), 0x7FF800000000)]);
+
+        let mut out = String::new();
+
+        offsets
+            .write_hpp(
+                &mut Formatter::new(&mut out, FormatOptions::default()).with_module_bases(&bases),
+            )
+            .unwrap();
+
+        assert!(
+            out.contains(
+                "constexpr std::ptrdiff_t dwEntityList = 0x1234; // abs: 0x7FF800001234\n"
+            )
+        );
+        assert!(out.contains("constexpr std::ptrdiff_t dwBuildNumber = 0x9ABC;\n"));
+        assert!(out.contains("constexpr std::ptrdiff_t dwEntityList_chunkSize = 0x200;\n"));
+        assert!(out.contains("constexpr std::ptrdiff_t dwEntityList_field = 0x40;\n"));
+        assert!(out.contains("// Image base: 0x7FF800000000 (absolute addresses"));
+    }
+
+    #[test]
+    fn hpp_bundle() {
+        let patterns = PatternMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwEntityList".to_string(), "488935${'} 4885f6".to_string())]),
+        )]);
+
+        let out = render(|fmt| write_hpp_bundle(&fixture(), &patterns, fmt));
+
+        assert_eq!(out.matches("#pragma once").count(), 1);
+
+        let offsets = out.find("    namespace offsets {\n").unwrap();
+        let patterns = out.find("\n\n    namespace patterns {\n").unwrap();
+
+        assert!(offsets < patterns);
+        assert!(out.contains("constexpr std::ptrdiff_t dwEntityList = 0x1234;\n"));
+        assert!(out.contains("constexpr const char* dwEntityList = \"488935${'} 4885f6\";\n"));
+        assert!(out.ends_with("    }\n}\n"));
+    }
+
+    #[test]
+    fn pretty_patterns() {
+        let options = FormatOptions {
+            pretty_patterns: true,
+            ..Default::default()
+        };
+
+        let patterns = PatternMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwEntityList".to_string(), "488935${'} 4885f6".to_string())]),
+        )]);
+
+        let out = render_with(options, |fmt| write_hpp_bundle(&fixture(), &patterns, fmt));
+
+        assert!(out.contains("constexpr const char* dwEntityList = \"48 89 35 ${'} 48 85 f6\";\n"));
+
+        let mut out = String::new();
+        let fmt = Formatter::new(&mut out, options);
+
+        assert_eq!(
+            fmt.pattern("488b80u4 4885c0 74? 803800 74"),
+            "48 8b 80 u4 48 85 c0 74 ? 80 38 00 74"
+        );
+        assert_eq!(
+            fmt.pattern("e8[4-8]\"abc\"(48|49)"),
+            "e8 [4-8] \"abc\" (48 | 49)"
+        );
+
+        for (module_name, sources) in crate::analysis::patterns() {
+            for (name, source) in sources {
+                assert_eq!(
+                    pelite::pattern::parse(&fmt.pattern(&source)).unwrap(),
+                    pelite::pattern::parse(&source).unwrap(),
+                    "{}: {}",
+                    module_name,
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cs_partial() {
+        let out = render(|fmt| write_cs_partial(&fixture(), fmt));
+
+        assert_eq!(
+            out,
+            "namespace CS2Dumper {\n    \
+             public static partial class Offsets {\n        \
+             // Module: client.dll\n        \
+             public const nint client_dll_dwEntityList = 0x1234;\n        \
+             public const nint client_dll_dwViewMatrix = 0x5678;\n        \
+             // Module: engine2.dll\n        \
+             public const nint engine2_dll_dwBuildNumber = 0x9ABC;\n    \
+             }\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn cs_serializable() {
+        let out = render(|fmt| write_cs_serializable(&fixture(), fmt));
+
+        assert_eq!(
+            out,
+            "using System;\n\n\
+             namespace CS2Dumper {\n    \
+             // Module: client.dll\n    \
+             [Serializable]\n    \
+             public class ClientDll {\n        \
+             public long dwEntityList = 0x1234;\n        \
+             public long dwViewMatrix = 0x5678;\n    \
+             }\n    \
+             // Module: engine2.dll\n    \
+             [Serializable]\n    \
+             public class Engine2Dll {\n        \
+             public long dwBuildNumber = 0x9ABC;\n    \
+             }\n    \
+             [Serializable]\n    \
+             public class Offsets {\n        \
+             public ClientDll client_dll = new ClientDll();\n        \
+             public Engine2Dll engine2_dll = new Engine2Dll();\n    \
+             }\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn java() {
+        let mut offsets = fixture();
+
+        offsets
+            .get_mut("client.dll")
+            .unwrap()
+            .extend([("2dRadar".to_string(), 0x10), ("class".to_string(), 0x20)]);
+
+        let out = render(|fmt| write_java(&offsets, fmt));
+
+        let expected = [
+            "public final class Offsets {",
+            "    // Module: client.dll",
+            "    public static final class client_dll {",
+            "        public static final long _2dRadar = 0x10L;",
+            "        public static final long class_ = 0x20L;",
+            "        public static final long dwEntityList = 0x1234L;",
+            "        public static final long dwViewMatrix = 0x5678L;",
+            "    }",
+            "    // Module: engine2.dll",
+            "    public static final class engine2_dll {",
+            "        public static final long dwBuildNumber = 0x9ABCL;",
+            "    }",
+            "}",
+        ];
+
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn frida() {
+        let out = render(|fmt| write_frida(&fixture(), fmt));
+
+        let expected = [
+            "export const offsets = {",
+            "    'client.dll': {",
+            "        dwEntityList: 0x1234,",
+            "        dwViewMatrix: 0x5678,",
+            "    },",
+            "    'engine2.dll': {",
+            "        dwBuildNumber: 0x9ABC,",
+            "    },",
+            "};",
+            "",
+            "export function resolve(moduleName, name) {",
+            "    return Process.getModuleByName(moduleName).base.add(offsets[moduleName][name]);",
+            "}",
+        ];
+
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn js() {
+        let client = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwEntityList".to_string(), 0x1234),
+                ("dwEntityList_chunkSize".to_string(), 0x200),
+            ]),
+        )]);
+
+        let out = render(|fmt| write_js(&client, false, fmt));
+
+        assert!(out.contains("dwEntityList: 0x1234,"));
+        assert!(!out.contains("addresses"));
+
+        let bases = ModuleBases::from([("client.dll".to_string(), 0x7FF800000000)]);
+
+        let mut out = String::new();
+
+        write_js(
+            &client,
+            true,
+            &mut Formatter::new(&mut out, FormatOptions::default()).with_module_bases(&bases),
+        )
+        .unwrap();
+
+        assert!(out.contains("dwEntityList: 0x1234n,"));
+        assert!(out.contains("export const addresses = {"));
+        assert!(out.contains("dwEntityList: 0x7FF800001234n,"));
+        assert!(out.contains("'client.dll': 0x7FF800000000n,"));
+
+        // The constant is only in `offsets`, not in `addresses`.
+        assert!(out.contains("dwEntityList_chunkSize: 0x200n,"));
+        assert!(!out.contains("dwEntityList_chunkSize: 0x7FF800000200n,"));
+    }
+
+    #[test]
+    fn json_ids() {
+        assert_eq!(offset_id("client.dll", "dwEntityList"), 256548622);
+        assert_eq!(offset_id("engine2.dll", "dwBuildNumber"), 2288426039);
+
+        let ids: Vec<_> = catalog()
+            .into_iter()
+            .map(|(module_name, name, _)| offset_id(module_name, name))
+            .collect();
+
+        assert!(ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id)));
+
+        let out = render(|fmt| write_json_ids(&fixture(), fmt));
+
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(value["offsets"]["256548622"], 0x1234);
+        assert_eq!(value["names"]["256548622"], "client.dll::dwEntityList");
+        assert_eq!(value["names"]["2288426039"], "engine2.dll::dwBuildNumber");
+        assert_eq!(value["names"].as_object().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn env() {
+        let out = render(|fmt| write_env(&fixture(), false, fmt));
+
+        assert_eq!(
+            out,
+            "export CLIENT_DLL_DW_ENTITY_LIST=0x1234\n\
+             export CLIENT_DLL_DW_VIEW_MATRIX=0x5678\n\
+             export ENGINE2_DLL_DW_BUILD_NUMBER=0x9ABC\n"
+        );
+
+        let out = render(|fmt| write_env(&fixture(), true, fmt));
+
+        assert!(out.starts_with("set CLIENT_DLL_DW_ENTITY_LIST=0x1234\n"));
+    }
+
+    #[test]
+    fn constant_case() {
+        let names = [
+            "dwCSGOInput",
+            "dwViewMatrix",
+            "client_dll",
+            "engine2_dll",
+            "m_pGameRules",
+        ];
+
+        let render_case = |constant_case| {
+            let options = FormatOptions {
+                constant_case,
+                ..Default::default()
+            };
+
+            let mut out = String::new();
+            let fmt = Formatter::new(&mut out, options);
+
+            names.map(|name| fmt.constant_name(name))
+        };
+
+        assert_eq!(
+            render_case(ConstantCase::Acronyms),
+            [
+                "DW_CSGO_INPUT",
+                "DW_VIEW_MATRIX",
+                "CLIENT_DLL",
+                "ENGINE2_DLL",
+                "M_P_GAME_RULES"
+            ]
+        );
+        assert_eq!(
+            render_case(ConstantCase::Naive),
+            [
+                "DW_CSGOINPUT",
+                "DW_VIEW_MATRIX",
+                "CLIENT_DLL",
+                "ENGINE2_DLL",
+                "M_P_GAME_RULES"
+            ]
+        );
+    }
+
+    #[test]
+    fn mjs() {
+        let out = render(|fmt| write_mjs(&fixture(), fmt));
+
+        assert_eq!(
+            out,
+            "export const offsets = {\n    \
+             'client.dll': {\n        \
+             'dwEntityList': 0x1234,\n        \
+             'dwViewMatrix': 0x5678,\n    \
+             },\n    \
+             'engine2.dll': {\n        \
+             'dwBuildNumber': 0x9ABC,\n    \
+             },\n\
+             };\n\
+             \n\
+             export default offsets;\n"
+        );
+    }
+
+    #[test]
+    fn rs_phf() {
+        let out = render(|fmt| write_rs_phf(&fixture(), fmt));
+
+        assert!(out.starts_with(
+            "// Module: client.dll\n\
+             pub static CLIENT_DLL: phf::Map<&'static str, u32> = ::phf::Map {"
+        ));
+        assert!(out.contains("pub static ENGINE2_DLL: phf::Map<&'static str, u32> = ::phf::Map {"));
+
+        for entry in [
+            "(\"dwEntityList\", 0x1234)",
+            "(\"dwViewMatrix\", 0x5678)",
+            "(\"dwBuildNumber\", 0x9ABC)",
+        ] {
+            assert!(out.contains(entry), "{}", entry);
+        }
+
+        assert!(out.ends_with("};\n"));
+    }
+
+    #[test]
+    fn ts_enum() {
+        let out = render(|fmt| write_ts_enum(&fixture(), fmt));
+
+        assert_eq!(
+            out,
+            "// Module: client.dll\n\
+             export const enum ClientDll {\n    \
+             dwEntityList = 0x1234,\n    \
+             dwViewMatrix = 0x5678,\n\
+             }\n\
+             \n\
+             // Module: engine2.dll\n\
+             export const enum Engine2Dll {\n    \
+             dwBuildNumber = 0x9ABC,\n\
+             }\n"
+        );
+
+        assert_eq!(
+            ts_identifier("m_pGameRules.m_bWarmup"),
+            "m_pGameRules_m_bWarmup"
+        );
+        assert_eq!(ts_identifier("2dSkybox"), "_2dSkybox");
+    }
+
+    #[test]
+    fn raw_json_keys() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("player.health".to_string(), 0x1234)]),
+        )]);
+
+        let out = render(|fmt| offsets.write_json(fmt));
+
+        assert!(out.contains("\"player.health\": 4660"));
+
+        let out = render(|fmt| write_json_array(&offsets, &ExpressionMap::new(), fmt));
+
+        assert!(out.contains("\"name\": \"player.health\""));
+
+        let out = render(|fmt| offsets.write_cs(fmt));
+
+        assert!(out.contains("public const nint player_health = 0x1234;"));
+
+        let out = render(|fmt| offsets.write_hpp(fmt));
+
+        assert!(out.contains("constexpr std::ptrdiff_t player_health = 0x1234;"));
+
+        let out = render(|fmt| offsets.write_rs(fmt));
+
+        assert!(out.contains("pub const player_health: usize = 0x1234;"));
+
+        let out = render(|fmt| write_js(&offsets, false, fmt));
+
+        assert!(out.contains("player_health: 0x1234,"));
+    }
+
+    #[test]
+    fn json_target() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwEntityList".to_string(), 0x1234),
+                ("dwGlobalVars".to_string(), 0x5678),
+            ]),
+        )]);
+
+        let out = render(|fmt| offsets.write_json(fmt));
+
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(value["client.dll"]["dwEntityList"], 0x1234);
+        assert_eq!(
+            value["client.dll"]["dwGlobalVars"],
+            json!({"value": 0x5678, "target": "gpGlobals"})
+        );
+
+        let out = render(|fmt| offsets.write_hpp(fmt));
+
+        assert!(out.contains("// Target: gpGlobals\n"));
+    }
+
+    #[test]
+    fn signed_offsets() {
+        let offsets = OffsetMap::from([(
+            "soundsystem.dll".to_string(),
+            BTreeMap::from([
+                ("dwSoundSystem".to_string(), 0x1234),
+                ("dwSoundSystem_engineViewData".to_string(), -0x10i32 as u32),
+            ]),
+        )]);
+
+        let rs = render(|fmt| offsets.write_rs(fmt));
+
+        assert!(rs.contains("pub const dwSoundSystem: usize = 0x1234;\n"));
+        assert!(rs.contains("pub const dwSoundSystem_engineViewData: isize = -0x10;\n"));
+
+        let hpp = render(|fmt| offsets.write_hpp(fmt));
+
+        assert!(hpp.contains("constexpr std::ptrdiff_t dwSoundSystem_engineViewData = -0x10;\n"));
+
+        let cs = render(|fmt| offsets.write_cs(fmt));
+
+        assert!(cs.contains("public const nint dwSoundSystem_engineViewData = -0x10;\n"));
+
+        let json = render(|fmt| offsets.write_json(fmt));
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            value["soundsystem.dll"]["dwSoundSystem_engineViewData"],
+            -0x10
+        );
+
+        let options = FormatOptions {
+            radix: Radix::Dec,
+            ..Default::default()
+        };
+
+        let rs = render_with(options, |fmt| offsets.write_rs(fmt));
+
+        assert!(rs.contains("pub const dwSoundSystem_engineViewData: isize = -16;\n"));
+
+        let cs_enum = render(|fmt| write_cs_enum(&offsets, fmt));
+
+        assert!(cs_enum.contains("public enum Offsets : long"));
+        assert!(cs_enum.contains("soundsystem_dll_dwSoundSystem_engineViewData = -0x10,\n"));
+
+        let ct = render(|fmt| write_ct(&offsets, fmt));
+
+        assert!(ct.contains("<Address>soundsystem.dll-10</Address>"));
+
+        let phf = render(|fmt| write_rs_phf(&offsets, fmt));
+
+        assert!(phf.contains("phf::Map<&'static str, i64>"));
+        assert!(phf.contains("-0x10"));
+        assert!(!phf.contains("0xFFFFFFF0"));
+    }
+
+    #[test]
+    fn view_matrix_layout() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwViewMatrix".to_string(), 0x1234)]),
+        )]);
+
+        let out = render(|fmt| offsets.write_json(fmt));
+
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(
+            value["client.dll"]["dwViewMatrix"],
+            json!({"value": 0x1234, "layout": "float[4][4] row-major"})
+        );
+
+        let out = render(|fmt| offsets.write_cs(fmt));
+
+        assert!(out.contains("// Layout: float[4][4] row-major\n"));
+    }
+
+    #[test]
+    fn mask() {
+        let entries: [(&str, &str, &[Atom]); 2] = [
+            (
+                "client.dll",
+                "dwEntityList",
+                pelite::pattern!("488935${'} 4885f6"),
+            ),
+            ("client.dll", "dwCreateInterface", &[]),
+        ];
+
+        let out = render(|fmt| write_mask_bytes(&entries, fmt));
+
+        assert!(out.contains("namespace client_dll {"));
+        assert!(out.contains(
+            "constexpr unsigned char dwEntityList_bytes[] = { 0x48, 0x89, 0x35, 0x00, 0x00, 0x00, 0x00, 0x48, 0x85, 0xF6 };"
+        ));
+        assert!(out.contains("constexpr const char* dwEntityList_mask = \"xxx????xxx\";"));
+        assert!(!out.contains("dwCreateInterface"));
+    }
+
+    #[test]
+    fn json_array() {
+        let out = render(|fmt| write_json_array(&fixture(), &fixture_expressions(), fmt));
+
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(
+            value,
+            json!([
+                {"module": "client.dll", "name": "dwEntityList", "rva": 4660, "expression": "client.dll + 0x1234"},
+                {"module": "client.dll", "name": "dwViewMatrix", "rva": 22136, "expression": "client.dll + 0x5678"},
+                {"module": "engine2.dll", "name": "dwBuildNumber", "rva": 39612, "expression": null},
+            ])
+        );
+    }
+
+    #[test]
+    fn dbg_map() {
+        let mut offsets = fixture();
+
+        offsets.get_mut("client.dll").unwrap().extend([
+            ("dwGlobalVars".to_string(), 0x9000),
+            ("dwGlobalVars_mapName".to_string(), 0x188),
+        ]);
+
+        crate::analysis::add_constants(&mut offsets);
+
+        let out =
+            render(|fmt| write_dbg_map(&offsets, &crate::analysis::expressions(&offsets), fmt));
+
+        assert_eq!(
+            out,
+            "client.dll+0x1234 dwEntityList\n\
+             client.dll+0x9000 dwGlobalVars\n\
+             client.dll+0x5678 dwViewMatrix\n\
+             engine2.dll+0x9ABC dwBuildNumber\n"
+        );
+    }
+
+    #[test]
+    fn ndjson() {
+        let out = render(|fmt| write_ndjson(&fixture(), &fixture_expressions(), fmt));
+
+        let records: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0],
+            json!({
+                "module": "client.dll",
+                "name": "dwEntityList",
+                "rva": 4660,
+                "expression": "client.dll + 0x1234",
+            })
+        );
+    }
+
+    #[test]
+    fn rs_build() {
+        let out = render(|fmt| write_rs_build(&fixture(), fmt));
+
+        let expected = [
+            "// Module: client.dll",
+            "#[allow(dead_code, non_upper_case_globals)]",
+            "pub mod client_dll {",
+            "    pub const dwEntityList: usize = 0x1234;",
+            "    pub const dwViewMatrix: usize = 0x5678;",
+            "}",
+            "// Module: engine2.dll",
+            "#[allow(dead_code, non_upper_case_globals)]",
+            "pub mod engine2_dll {",
+            "    pub const dwBuildNumber: usize = 0x9ABC;",
+            "}",
+        ];
+
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+        assert!(!out.contains("#!"));
+    }
+
+    #[test]
+    fn sort_by_value() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwA".to_string(), 0x30),
+                ("dwB".to_string(), 0x10),
+                ("dwC".to_string(), 0x20),
+            ]),
+        )]);
+
+        let lines = |sort_by| {
+            let options = FormatOptions {
+                sort_by,
+                ..Default::default()
+            };
+
+            render_with(options, |fmt| offsets.write_hpp(fmt))
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("constexpr std::ptrdiff_t "))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lines(SortBy::Name),
+            ["dwA = 0x30;", "dwB = 0x10;", "dwC = 0x20;"]
+        );
+        assert_eq!(
+            lines(SortBy::Value),
+            ["dwB = 0x10;", "dwC = 0x20;", "dwA = 0x30;"]
+        );
+    }
+
+    #[test]
+    fn ct() {
+        let out = render(|fmt| write_ct(&fixture(), fmt));
+
+        let lines: Vec<_> = out.lines().map(str::trim).collect();
+
+        let i = lines
+            .iter()
+            .position(|&line| line == "<Description>\"dwEntityList\"</Description>")
+            .unwrap();
+
+        assert_eq!(lines[0], "<?xml version=\"1.0\" encoding=\"utf-8\"?>");
+        assert_eq!(lines[i + 3], "<Address>client.dll+1234</Address>");
+        assert_eq!(out.matches("<CheatEntry>").count(), 5);
+    }
+
+    #[test]
+    fn ex() {
+        let out = render(|fmt| write_ex(&fixture(), fmt));
+
+        let expected = [
+            "defmodule CS2Dumper.Offsets do",
+            "    def offsets do",
+            "        %{",
+            "            \"client.dll\" => %{",
+            "                \"dwEntityList\" => 0x1234,",
+            "                \"dwViewMatrix\" => 0x5678",
+            "            },",
+            "            \"engine2.dll\" => %{",
+            "                \"dwBuildNumber\" => 0x9ABC",
+            "            }",
+            "        }",
+            "    end",
+            "end\n",
+        ];
+
+        assert_eq!(out, expected.join("\n"));
+    }
+
+    #[test]
+    fn json_compact() {
+        let out = render(|fmt| fmt.compact(|fmt| fixture().write_json(fmt)));
+
+        assert!(!out.contains('\n'));
+        assert_eq!(
+            out,
+            r#"{"client.dll":{"dwEntityList":4660,"dwViewMatrix":{"layout":"float[4][4] row-major","value":22136}},"engine2.dll":{"dwBuildNumber":39612},"schema_version":5}"#
+        );
+
+        assert!(render(|fmt| fixture().write_json(fmt)).contains('\n'));
+    }
+
+    #[test]
+    fn hex_width() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwSmall".to_string(), 0x40),
+                ("dwLarge".to_string(), 0x1ABCDEF0),
+            ]),
+        )]);
+
+        let lines = |hex_width| {
+            let options = FormatOptions {
+                hex_width,
+                ..Default::default()
+            };
+
+            render_with(options, |fmt| offsets.write_rs(fmt))
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("pub const "))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            lines(0),
+            ["dwLarge: usize = 0x1ABCDEF0;", "dwSmall: usize = 0x40;"]
+        );
+        assert_eq!(
+            lines(8),
+            [
+                "dwLarge: usize = 0x1ABCDEF0;",
+                "dwSmall: usize = 0x00000040;"
+            ]
+        );
+    }
+
+    #[test]
+    fn radix() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwEntityList".to_string(), 0x1234)]),
+        )]);
+
+        let render_radix = |radix| {
+            let options = FormatOptions {
+                radix,
+                hex_width: 8,
+                ..Default::default()
+            };
+
+            let rs = render_with(options, |fmt| offsets.write_rs(fmt));
+            let json = render_with(options, |fmt| offsets.write_json(fmt));
+
+            (rs, json)
+        };
+
+        let (rs, json) = render_radix(Radix::Hex);
+
+        assert!(rs.contains("pub const dwEntityList: usize = 0x00001234;\n"));
+        assert!(json.contains("\"dwEntityList\": 4660"));
+
+        let (rs, json) = render_radix(Radix::Dec);
+
+        assert!(rs.contains("pub const dwEntityList: usize = 4660;\n"));
+        assert!(json.contains("\"dwEntityList\": 4660"));
+    }
+
+    #[test]
+    fn hex_case() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([("dwEntityList".to_string(), 0x1ABCD)]),
+        )]);
+
+        let render_case = |hex_case| {
+            let options = FormatOptions {
+                hex_case,
+                ..Default::default()
+            };
+
+            render_with(options, |fmt| offsets.write_rs(fmt))
+        };
+
+        assert!(render_case(HexCase::Upper).contains("pub const dwEntityList: usize = 0x1ABCD;\n"));
+        assert!(render_case(HexCase::Lower).contains("pub const dwEntityList: usize = 0x1abcd;\n"));
+    }
+}
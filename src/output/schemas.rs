@@ -49,7 +49,7 @@ impl CodeWriter for SchemaMap {
                                                     type_name, member.value
                                                 )
                                             } else {
-                                                format!("{:#X}", member.value)
+                                                fmt.hex(member.value, 0)
                                             };
                                             format!("{} = {}", member.name, hex)
                                         })
@@ -80,8 +80,10 @@ impl CodeWriter for SchemaMap {
                                     for field in &class.fields {
                                         writeln!(
                                             fmt,
-                                            "public const nint {} = {:#X}; // {}",
-                                            field.name, field.offset, field.type_name
+                                            "public const nint {} = {}; // {}",
+                                            field.name,
+                                            fmt.number(field.offset),
+                                            field.type_name
                                         )?;
                                     }
 
@@ -134,7 +136,11 @@ impl CodeWriter for SchemaMap {
                                             .members
                                             .iter()
                                             .map(|member| {
-                                                format!("{} = {:#X}", member.name, member.value)
+                                                format!(
+                                                    "{} = {}",
+                                                    member.name,
+                                                    fmt.hex(member.value, 0)
+                                                )
                                             })
                                             .collect::<Vec<_>>()
                                             .join(",\n");
@@ -163,8 +169,10 @@ impl CodeWriter for SchemaMap {
                                         for field in &class.fields {
                                             writeln!(
                                                 fmt,
-                                                "constexpr std::ptrdiff_t {} = {:#X}; // {}",
-                                                field.name, field.offset, field.type_name
+                                                "constexpr std::ptrdiff_t {} = {}; // {}",
+                                                field.name,
+                                                fmt.number(field.offset),
+                                                field.type_name
                                             )?;
                                         }
 
@@ -217,7 +225,7 @@ impl CodeWriter for SchemaMap {
                             .collect();
 
                         (
-                            slugify(&class.name),
+                            &class.name,
                             json!({
                                 "parent": class.parent.as_ref().map(|parent| &parent.name),
                                 "fields": fields,
@@ -245,7 +253,7 @@ impl CodeWriter for SchemaMap {
                         };
 
                         (
-                            slugify(&enum_.name),
+                            &enum_.name,
                             json!({
                                 "alignment": enum_.alignment,
                                 "type": type_name,
@@ -265,7 +273,7 @@ impl CodeWriter for SchemaMap {
             })
             .collect();
 
-        fmt.write_str(&serde_json::to_string_pretty(&content).unwrap())
+        fmt.write_json_file(&content)
     }
 
     fn write_rs(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -316,7 +324,7 @@ impl CodeWriter for SchemaMap {
                                                     let value = if member.value == -1 {
                                                         format!("{}::MAX", type_name)
                                                     } else {
-                                                        format!("{:#X}", member.value)
+                                                        fmt.hex(member.value, 0)
                                                     };
 
                                                     Some(format!("{} = {}", member.name, value))
@@ -351,8 +359,10 @@ impl CodeWriter for SchemaMap {
                                         for field in &class.fields {
                                             writeln!(
                                                 fmt,
-                                                "pub const {}: usize = {:#X}; // {}",
-                                                field.name, field.offset, field.type_name
+                                                "pub const {}: usize = {}; // {}",
+                                                field.name,
+                                                fmt.number(field.offset),
+                                                field.type_name
                                             )?;
                                         }
 
@@ -0,0 +1,124 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Opens the named pipe at `path` for writing, or returns `None` if nothing is reading from it
+/// instead of waiting for a reader. On Windows, the reader has to create the pipe
+/// (`\\.\pipe\<name>`), on Unix it's a FIFO created with `mkfifo`.
+pub fn open(path: &Path) -> Result<Option<File>> {
+    match open_nonblocking(path) {
+        Ok(file) => Ok(Some(file)),
+        Err(err) if is_no_reader(&err) => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("unable to open pipe {}", path.display())),
+    }
+}
+
+#[cfg(unix)]
+fn open_nonblocking(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::io::AsRawFd;
+
+    let file = OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)?;
+
+    // Only opening shouldn't block. Writes wait for the reader to keep up, so that large dumps
+    // aren't cut off once the pipe's buffer is full.
+    let fd = file.as_raw_fd();
+
+    // SAFETY: `fd` is a valid file descriptor owned by `file`.
+    let result = unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+
+        if flags < 0 {
+            flags
+        } else {
+            libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK)
+        }
+    };
+
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(file)
+}
+
+#[cfg(unix)]
+fn is_no_reader(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::ENXIO)
+}
+
+#[cfg(windows)]
+fn open_nonblocking(path: &Path) -> io::Result<File> {
+    OpenOptions::new().write(true).open(path)
+}
+
+#[cfg(windows)]
+fn is_no_reader(err: &io::Error) -> bool {
+    const ERROR_FILE_NOT_FOUND: i32 = 2;
+    const ERROR_PIPE_BUSY: i32 = 231;
+
+    // The pipe doesn't exist until a reader creates it, and is busy while all of its instances
+    // are connected to other writers.
+    matches!(
+        err.raw_os_error(),
+        Some(ERROR_FILE_NOT_FOUND | ERROR_PIPE_BUSY)
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::ffi::CString;
+    use std::io::Write;
+    use std::os::unix::ffi::OsStrExt;
+    use std::time::Duration;
+    use std::{env, fs, thread};
+
+    #[test]
+    fn fifo() -> Result<()> {
+        let path = env::temp_dir().join(format!("cs2-dumper-pipe-{}", std::process::id()));
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+
+        // SAFETY: `c_path` is a valid NUL-terminated path.
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        assert!(open(&path)?.is_none());
+
+        let reader = {
+            let path = path.clone();
+
+            thread::spawn(move || fs::read_to_string(path))
+        };
+
+        // The reader may not have opened the pipe yet.
+        let mut pipe = None;
+
+        for _ in 0..500 {
+            pipe = open(&path)?;
+
+            if pipe.is_some() {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut pipe = pipe.expect("reader didn't open the pipe");
+
+        pipe.write_all(b"{\"client.dll\": {}}\n")?;
+
+        drop(pipe);
+
+        assert_eq!(reader.join().unwrap()?, "{\"client.dll\": {}}\n");
+
+        fs::remove_file(&path)?;
+
+        Ok(())
+    }
+}
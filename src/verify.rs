@@ -0,0 +1,374 @@
+use anyhow::{Result, anyhow, bail};
+
+use memflow::prelude::v1::*;
+
+use crate::analysis::{self, AnalysisResult, ExpressionMap, ModuleBases, OffsetMap};
+
+/// The outcome of reading a known value from the live process with freshly dumped offsets.
+pub struct Check {
+    pub name: String,
+    /// The value that was read, or why it couldn't be read.
+    pub result: Result<String>,
+}
+
+/// Reads a few values that are known to be valid while in game, using the offsets in `result`.
+pub fn verify_live<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+) -> Vec<Check> {
+    let checks: [(&'static str, fn(&mut P, &AnalysisResult) -> Result<String>); 3] = [
+        ("build number", build_number),
+        ("window size", window_size),
+        ("local player name", local_player_name),
+    ];
+
+    checks
+        .into_iter()
+        .map(|(name, check)| Check {
+            name: name.to_string(),
+            result: check(process, result),
+        })
+        .collect()
+}
+
+/// Reads a `u32` at `count` offsets sampled from `result` with `seed`, which only checks that the
+/// offsets point to readable memory. The same seed samples the same offsets. Only addresses in
+/// their module are sampled, since constants and fields aren't relative to the module base.
+pub fn verify_sample<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+    count: usize,
+    seed: u64,
+) -> Vec<Check> {
+    let bases = analysis::module_bases(process, &result.offsets);
+    let expressions = analysis::expressions(&result.offsets);
+
+    read_sample(
+        &result.offsets,
+        &expressions,
+        &bases,
+        count,
+        seed,
+        |address| Ok(process.read(Address::from(address)).data_part()?),
+    )
+}
+
+fn read_sample<F>(
+    offsets: &OffsetMap,
+    expressions: &ExpressionMap,
+    bases: &ModuleBases,
+    count: usize,
+    seed: u64,
+    mut read: F,
+) -> Vec<Check>
+where
+    F: FnMut(u64) -> Result<u32>,
+{
+    sample(offsets, expressions, count, seed)
+        .into_iter()
+        .map(|(module_name, name, value)| {
+            let result = bases
+                .get(module_name)
+                .ok_or_else(|| anyhow!("module not found: {}", module_name))
+                .and_then(|base| {
+                    let address = base + value as u64;
+
+                    read(address).map(|data| format!("{:#X} at {:#X}", data, address))
+                });
+
+            Check {
+                name: format!("{}::{}", module_name, name),
+                result,
+            }
+        })
+        .collect()
+}
+
+/// Picks up to `count` addresses in a random order that only depends on `seed`. Offsets without an
+/// expression are assumed to be addresses.
+fn sample<'a>(
+    offsets: &'a OffsetMap,
+    expressions: &ExpressionMap,
+    count: usize,
+    seed: u64,
+) -> Vec<(&'a str, &'a str, u32)> {
+    let mut entries: Vec<_> = offsets
+        .iter()
+        .flat_map(|(module_name, offsets)| {
+            let expressions = expressions.get(module_name);
+
+            offsets
+                .iter()
+                .filter(move |(name, _)| {
+                    expressions
+                        .and_then(|expressions| expressions.get(*name))
+                        .is_none_or(|expression| analysis::is_address(expression))
+                })
+                .map(move |(name, value)| (module_name.as_str(), name.as_str(), *value))
+        })
+        .collect();
+
+    let count = count.min(entries.len());
+    let mut state = seed;
+
+    // A partial Fisher-Yates shuffle of the first `count` entries.
+    for i in 0..count {
+        let j = i + (splitmix64(&mut state) % (entries.len() - i) as u64) as usize;
+
+        entries.swap(i, j);
+    }
+
+    entries.truncate(count);
+    entries
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+
+    let mut z = *state;
+
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+
+    z ^ (z >> 31)
+}
+
+/// Prints one row per check, returning an error if any of them failed.
+pub fn print_summary(checks: &[Check]) -> Result<()> {
+    let width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or(0);
+
+    for check in checks {
+        let (status, detail) = match &check.result {
+            Ok(value) => ("PASS", value.clone()),
+            Err(err) => ("FAIL", err.to_string()),
+        };
+
+        println!("{:<width$}  {}  {}", check.name, status, detail);
+    }
+
+    let failed = checks.iter().filter(|check| check.result.is_err()).count();
+
+    if failed > 0 {
+        bail!("{} of {} checks failed", failed, checks.len());
+    }
+
+    Ok(())
+}
+
+fn build_number<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+) -> Result<String> {
+    let build_number = read_u32(process, result, "engine2.dll", "dwBuildNumber")?;
+
+    if build_number == 0 {
+        bail!("build number is zero");
+    }
+
+    Ok(build_number.to_string())
+}
+
+fn window_size<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+) -> Result<String> {
+    let width = read_u32(process, result, "engine2.dll", "dwWindowWidth")?;
+    let height = read_u32(process, result, "engine2.dll", "dwWindowHeight")?;
+
+    if width == 0 || height == 0 {
+        bail!("window size is {}x{}", width, height);
+    }
+
+    Ok(format!("{}x{}", width, height))
+}
+
+fn local_player_name<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+) -> Result<String> {
+    let controller = read_addr(process, result, "client.dll", "dwLocalPlayerController")?;
+
+    if controller.is_null() {
+        bail!("no local player controller, not in game?");
+    }
+
+    let name_offset = class_field_offset(
+        result,
+        "client.dll",
+        "CBasePlayerController",
+        "m_iszPlayerName",
+    )?;
+
+    let name = process
+        .read_utf8(controller + name_offset, 4096)
+        .data_part()?;
+
+    if name.is_empty() {
+        bail!("local player name is empty");
+    }
+
+    Ok(name)
+}
+
+fn offset_address<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+    module_name: &str,
+    offset_name: &str,
+) -> Result<Address> {
+    let offset = result
+        .offsets
+        .get(module_name)
+        .and_then(|offsets| offsets.get(offset_name))
+        .ok_or_else(|| anyhow!("offset not found: {}", offset_name))?;
+
+    let module = process.module_by_name(module_name)?;
+
+    Ok(module.base + *offset as umem)
+}
+
+fn read_u32<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+    module_name: &str,
+    offset_name: &str,
+) -> Result<u32> {
+    let addr = offset_address(process, result, module_name, offset_name)?;
+
+    Ok(process.read(addr).data_part()?)
+}
+
+fn read_addr<P: Process + MemoryView>(
+    process: &mut P,
+    result: &AnalysisResult,
+    module_name: &str,
+    offset_name: &str,
+) -> Result<Address> {
+    let addr = offset_address(process, result, module_name, offset_name)?;
+
+    Ok(process.read_addr64(addr).data_part()?)
+}
+
+fn class_field_offset(
+    result: &AnalysisResult,
+    module_name: &str,
+    class_name: &str,
+    field_name: &str,
+) -> Result<umem> {
+    let (classes, _) = result
+        .schemas
+        .get(module_name)
+        .ok_or_else(|| anyhow!("no schemas for module: {}", module_name))?;
+
+    classes
+        .iter()
+        .find(|class| class.name == class_name)
+        .and_then(|class| class.fields.iter().find(|field| field.name == field_name))
+        .map(|field| field.offset as umem)
+        .ok_or_else(|| anyhow!("field not found: {}::{}", class_name, field_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn sampled_reads() {
+        let offsets = OffsetMap::from([
+            (
+                "client.dll".to_string(),
+                BTreeMap::from([
+                    ("dwEntityList".to_string(), 0x1000),
+                    ("dwGameRules".to_string(), 0x2000),
+                    ("dwViewMatrix".to_string(), 0x3000),
+                ]),
+            ),
+            (
+                "engine2.dll".to_string(),
+                BTreeMap::from([("dwBuildNumber".to_string(), 0x4000)]),
+            ),
+        ]);
+
+        let bases = ModuleBases::from([("client.dll".to_string(), 0x10000)]);
+
+        let mut reads = Vec::new();
+
+        let none = ExpressionMap::new();
+
+        let checks = read_sample(&offsets, &none, &bases, 3, 42, |address| {
+            reads.push(address);
+
+            if address == 0x13000 {
+                bail!("unable to read memory");
+            }
+
+            Ok(1)
+        });
+
+        assert_eq!(checks.len(), 3);
+
+        // Offsets of modules without a base aren't read.
+        let missing = checks
+            .iter()
+            .filter(|check| check.name.starts_with("engine2.dll"))
+            .count();
+
+        assert_eq!(reads.len(), 3 - missing);
+
+        for check in &checks {
+            let failed =
+                check.name == "client.dll::dwViewMatrix" || check.name.starts_with("engine2.dll");
+
+            assert_eq!(check.result.is_err(), failed, "{}", check.name);
+        }
+
+        let names = |seed| {
+            read_sample(&offsets, &none, &bases, 3, seed, |_| Ok(0))
+                .into_iter()
+                .map(|check| check.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(42), names(42));
+        assert_eq!(
+            checks.iter().map(|check| &check.name).collect::<Vec<_>>(),
+            names(42).iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            read_sample(&offsets, &none, &bases, 10, 42, |_| Ok(0)).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn sampled_addresses_only() {
+        let offsets = OffsetMap::from([(
+            "client.dll".to_string(),
+            BTreeMap::from([
+                ("dwEntityList".to_string(), 0x1000),
+                ("dwEntityList_chunkSize".to_string(), 0x200),
+                ("dwGlobalVars".to_string(), 0x2000),
+                ("dwGlobalVars_intervalPerTick".to_string(), 0x44),
+            ]),
+        )]);
+
+        let expressions = analysis::expressions(&offsets);
+
+        // Constants and fields would be read from the PE headers if they were sampled.
+        let names: Vec<_> = sample(&offsets, &expressions, 10, 42)
+            .into_iter()
+            .map(|(_, name, _)| name)
+            .collect();
+
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"dwEntityList"));
+        assert!(names.contains(&"dwGlobalVars"));
+    }
+}